@@ -0,0 +1,175 @@
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+use crate::{RunError, runner::RunResult, solver::Answer};
+
+/// Expected answers, stored as TOML tables keyed by day and part:
+///
+/// ```toml
+/// [day01]
+/// part1 = 514579
+/// part2 = 241861950
+/// ```
+pub struct Answers {
+    path: PathBuf,
+    days: BTreeMap<String, BTreeMap<String, toml::Value>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    Missing,
+    Error,
+}
+
+impl Answers {
+    /// Load the answers file, treating a missing file as empty.
+    pub fn load(path: &Path) -> Result<Answers, RunError> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(source) => return Err(RunError::IO { path: path.to_path_buf(), source }),
+        };
+
+        Ok(Answers {
+            path: path.to_path_buf(),
+            days: Self::parse(&text)?,
+        })
+    }
+
+    fn parse(text: &str) -> Result<BTreeMap<String, BTreeMap<String, toml::Value>>, RunError> {
+        toml::from_str(text).map_err(|e| RunError::ParseString(e.to_string()))
+    }
+
+    pub fn expected(&self, day: u8, part: u8) -> Option<String> {
+        let value = self.days
+            .get(&format!("day{:02}", day))?
+            .get(&format!("part{}", part))?;
+
+        match value {
+            toml::Value::String(text) => Some(text.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: &Answer) {
+        let value = match answer {
+            Answer::Int(value) => toml::Value::Integer(*value),
+            Answer::UInt(value) => i64::try_from(*value)
+                .map_or_else(|_| toml::Value::String(value.to_string()), toml::Value::Integer),
+            answer => toml::Value::String(answer.to_string()),
+        };
+
+        self.days
+            .entry(format!("day{:02}", day))
+            .or_default()
+            .insert(format!("part{}", part), value);
+    }
+
+    pub fn save(&self) -> Result<(), RunError> {
+        let text = toml::to_string(&self.days)
+            .map_err(|e| RunError::Serialize(e.to_string()))?;
+        fs::write(&self.path, text)
+            .map_err(|source| RunError::IO { path: self.path.clone(), source })?;
+
+        Ok(())
+    }
+
+    pub fn verdict(&self, result: &RunResult) -> Verdict {
+        let answer = match &result.answer {
+            Ok(answer) => answer.to_string(),
+            Err(_) => return Verdict::Error,
+        };
+
+        match self.expected(result.day, result.part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected),
+            None => Verdict::Missing,
+        }
+    }
+}
+
+pub fn print_table(answers: &Answers, results: &[RunResult]) {
+    println!("{:<6} {:<5} {:>16} {:>16} verdict",
+        "day", "part", "answer", "expected");
+
+    for result in results {
+        let answer = match &result.answer {
+            Ok(answer) => answer.summary(),
+            Err(_) => "-".to_string(),
+        };
+        let expected = answers.expected(result.day, result.part)
+            .map_or_else(|| "-".to_string(), |expected| expected.replace('\n', "/"));
+        let verdict = match answers.verdict(result) {
+            Verdict::Pass => "pass",
+            Verdict::Fail(_) => "FAIL",
+            Verdict::Missing => "missing",
+            Verdict::Error => "ERROR",
+        };
+
+        println!("{:<6} {:<5} {:>16} {:>16} {}",
+            format!("day{:02}", result.day),
+            result.part,
+            answer,
+            expected,
+            verdict);
+    }
+
+    for result in results {
+        if let Err(e) = &result.answer {
+            println!("day{:02} part {}: {}", result.day, result.part, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    static SAMPLE_ANSWERS: &str = "[day01]
+part1 = 514579
+part2 = \"241861950\"
+";
+
+    fn sample_answers() -> Answers {
+        Answers {
+            path: PathBuf::new(),
+            days: Answers::parse(SAMPLE_ANSWERS).unwrap(),
+        }
+    }
+
+    fn result(day: u8, part: u8, answer: usize) -> RunResult {
+        RunResult { year: Some(2020), day, part, answer: Ok(answer.into()), elapsed: Duration::ZERO }
+    }
+
+    #[test]
+    fn test_expected() {
+        let answers = sample_answers();
+        assert_eq!(answers.expected(1, 1), Some("514579".to_string()));
+        assert_eq!(answers.expected(1, 2), Some("241861950".to_string()));
+        assert_eq!(answers.expected(2, 1), None);
+    }
+
+    #[test]
+    fn test_verdict() {
+        let answers = sample_answers();
+        assert_eq!(answers.verdict(&result(1, 1, 514579)), Verdict::Pass);
+        assert_eq!(answers.verdict(&result(1, 2, 1)), Verdict::Fail("241861950".to_string()));
+        assert_eq!(answers.verdict(&result(3, 1, 7)), Verdict::Missing);
+    }
+
+    #[test]
+    fn test_record() {
+        let mut answers = sample_answers();
+        answers.record(1, 2, &Answer::UInt(42));
+        answers.record(3, 1, &Answer::Int(-7));
+        answers.record(3, 2, &Answer::Grid(vec!["#.".to_string(), ".#".to_string()]));
+        assert_eq!(answers.expected(1, 2), Some("42".to_string()));
+        assert_eq!(answers.expected(3, 1), Some("-7".to_string()));
+        assert_eq!(answers.expected(3, 2), Some("#.\n.#".to_string()));
+        assert_eq!(
+            Answers::parse(&toml::to_string(&answers.days).unwrap()).unwrap(),
+            answers.days);
+    }
+}
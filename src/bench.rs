@@ -0,0 +1,154 @@
+use std::{fs, hint, path::Path, time::{Duration, Instant}};
+
+use serde::Serialize;
+
+use crate::RunError;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+/// Timing summary for one phase, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Stats {
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+    pub stddev: u64,
+}
+
+impl Stats {
+    fn from_samples(samples: &mut [u64]) -> Stats {
+        samples.sort();
+
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<u64>() as f64 / count;
+        let variance = samples.iter()
+            .map(|&x| (x as f64 - mean).powi(2))
+            .sum::<f64>() / count;
+
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: mean.round() as u64,
+            stddev: variance.sqrt().round() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub day: u8,
+    pub name: &'static str,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+#[derive(Serialize)]
+struct BenchFile<'a> {
+    timestamp: u64,
+    warmup: usize,
+    iterations: usize,
+    results: &'a [BenchReport],
+}
+
+/// Run `f` `warmup` times untimed, then `iterations` times timed.
+pub fn measure<T, F>(options: &BenchOptions, mut f: F) -> Result<Stats, RunError>
+where F: FnMut() -> Result<T, RunError> {
+    if options.iterations == 0 {
+        return Err(RunError::BadIterations);
+    }
+
+    for _ in 0..options.warmup {
+        hint::black_box(f()?);
+    }
+
+    let mut samples: Vec<u64> = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        hint::black_box(f()?);
+        samples.push(start.elapsed().as_nanos() as u64);
+    }
+
+    Ok(Stats::from_samples(&mut samples))
+}
+
+pub fn format_ns(ns: u64) -> String {
+    format!("{:.2?}", Duration::from_nanos(ns))
+}
+
+pub fn print_table(reports: &[BenchReport]) {
+    println!("{:<6} {:<6} {:>12} {:>12} {:>12} {:>12}",
+        "day", "phase", "min", "median", "mean", "stddev");
+
+    for report in reports {
+        for (phase, stats) in [
+            ("parse", &report.parse),
+            ("part1", &report.part1),
+            ("part2", &report.part2)] {
+            println!("{:<6} {:<6} {:>12} {:>12} {:>12} {:>12}",
+                format!("day{:02}", report.day),
+                phase,
+                format_ns(stats.min),
+                format_ns(stats.median),
+                format_ns(stats.mean),
+                format_ns(stats.stddev));
+        }
+    }
+}
+
+pub fn write_json(path: &Path, options: &BenchOptions, reports: &[BenchReport]) -> Result<(), RunError> {
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    let file = BenchFile {
+        timestamp,
+        warmup: options.warmup,
+        iterations: options.iterations,
+        results: reports,
+    };
+
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| RunError::Serialize(e.to_string()))?;
+    fs::write(path, json)
+        .map_err(|source| RunError::IO { path: path.to_path_buf(), source })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(
+            Stats::from_samples(&mut [4, 1, 3, 2]),
+            Stats { min: 1, median: 2, mean: 3, stddev: 1 });
+        assert_eq!(
+            Stats::from_samples(&mut [5, 5, 5]),
+            Stats { min: 5, median: 5, mean: 5, stddev: 0 });
+    }
+
+    #[test]
+    fn test_measure() {
+        let options = BenchOptions { warmup: 2, iterations: 5 };
+        let mut calls = 0;
+        measure(&options, || { calls += 1; Ok(()) }).unwrap();
+        assert_eq!(calls, 7);
+
+        let options = BenchOptions { warmup: 0, iterations: 0 };
+        assert!(measure(&options, || Ok(())).is_err());
+    }
+}
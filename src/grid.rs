@@ -0,0 +1,305 @@
+use std::{fmt, ops::{Index, IndexMut}};
+
+use crate::{RunError, runerror::ParseError};
+
+/// Offsets to the four orthogonal neighbours, (dx, dy)
+#[cfg(test)]
+const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight surrounding neighbours, (dx, dy)
+#[cfg(test)]
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1),
+];
+
+/// A rectangular map stored row-major, addressed as (x, y) from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Result<Grid<T>, RunError> {
+        if cells.len() != width * height {
+            return Err(RunError::InputBounds);
+        }
+
+        Ok(Grid { width, height, cells })
+    }
+
+    /// Parse one row per line, converting each character with `cell`.
+    ///
+    /// Unknown characters and rows of the wrong width are reported against `data`
+    /// using `expected` to describe a valid cell. A final newline is ignored.
+    pub fn parse<F>(day: u8, data: &str, expected: &str, mut cell: F) -> Result<Grid<T>, RunError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let rows = data.strip_suffix('\n').unwrap_or(data);
+        let mut width = None;
+        let mut height = 0;
+        let mut cells: Vec<T> = vec![];
+
+        for line in rows.split('\n') {
+            let mut row_width = 0;
+
+            for (i, c) in line.char_indices() {
+                if width.is_some_and(|width| row_width == width) {
+                    return Err(ParseError::at(day, data, &line[i..], "end of row").into());
+                }
+
+                let value = cell(c)
+                    .ok_or_else(|| ParseError::at(day, data, &line[i..], expected))?;
+                cells.push(value);
+                row_width += 1;
+            }
+
+            match width {
+                Some(width) if row_width < width => {
+                    return Err(ParseError::at(day, data, &line[line.len()..],
+                        format!("{} more of {}", width - row_width, expected)).into());
+                },
+                Some(_) => {},
+                None => width = Some(row_width),
+            }
+
+            height += 1;
+        }
+
+        Grid::new(width.unwrap_or(0), height, cells)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+// General-purpose helpers no puzzle needs yet, built for their tests until one does
+#[cfg(test)]
+impl<T> Grid<T> {
+    /// Treat the grid as tiling the plane in both directions.
+    pub fn get_wrapping(&self, x: isize, y: isize) -> Option<&T> {
+        if self.cells.is_empty() {
+            return None;
+        }
+
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+
+        self.get(x, y)
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let cells = if x < self.width { &self.cells[x..] } else { &[] };
+
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Every cell with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.iter()
+            .enumerate()
+            .map(|(i, cell)| ((i % self.width, i / self.width), cell))
+    }
+
+    fn neighbours<'a>(&'a self, x: usize, y: usize, offsets: &'a [(isize, isize)])
+        -> impl Iterator<Item = ((usize, usize), &'a T)>
+    {
+        offsets.iter().filter_map(move |&(dx, dy)| {
+            let x = x.checked_add_signed(dx)?;
+            let y = y.checked_add_signed(dy)?;
+
+            self.get(x, y).map(|cell| ((x, y), cell))
+        })
+    }
+
+    /// Up, right, down and left of (x, y), skipping any off the edge.
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours(x, y, &ORTHOGONAL)
+    }
+
+    /// All eight cells around (x, y), skipping any off the edge.
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.neighbours(x, y, &SURROUNDING)
+    }
+}
+
+#[cfg(test)]
+impl<T: Clone> Grid<T> {
+    /// Build a new grid whose (x, y) cell is taken from `source(x, y)` of this one.
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (sx, sy) = source(x, y);
+                self.cells[sy * self.width + sx].clone()
+            })
+            .collect();
+
+        Grid { width, height, cells }
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        self.remap(self.height, self.width, |x, y| (y, self.height - 1 - x))
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Grid<T> {
+        self.remap(self.height, self.width, |x, y| (self.width - 1 - y, x))
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        self.remap(self.width, self.height, |x, y| (self.width - 1 - x, y))
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        self.remap(self.width, self.height, |x, y| (x, self.height - 1 - y))
+    }
+
+    pub fn transpose(&self) -> Grid<T> {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).expect("grid position out of bounds")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        self.get_mut(x, y).expect("grid position out of bounds")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SAMPLE_INPUT: &str = "ab.
+c.d";
+
+    fn sample() -> Grid<char> {
+        Grid::parse(0, SAMPLE_INPUT, "a cell", Some).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid, Grid::new(3, 2, "ab.c.d".chars().collect()).unwrap());
+        assert_eq!(Grid::parse(0, "ab.\nc.d\n", "a cell", Some).unwrap(), grid);
+        assert_eq!(grid.to_string(), SAMPLE_INPUT);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let error = |input: &str| match Grid::parse(0, input, "'.' or '#'", |c| "#.".contains(c).then_some(c)) {
+            Err(RunError::Parse(e)) => (e.line, e.column, e.expected),
+            _ => panic!("{input:?} should not parse"),
+        };
+
+        assert_eq!(error("#.\n.x"), (2, 2, "'.' or '#'".to_string()));
+        assert_eq!(error("#.\n.#."), (2, 3, "end of row".to_string()));
+        assert_eq!(error("#..\n.#"), (2, 3, "1 more of '.' or '#'".to_string()));
+        assert!(Grid::new(2, 2, vec![1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = sample();
+        assert_eq!(grid.get(2, 1), Some(&'d'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+        assert_eq!(grid.get_wrapping(4, 3), Some(&'.'));
+        assert_eq!(grid.get_wrapping(-1, -1), Some(&'d'));
+        assert_eq!(grid[(1, 0)], 'b');
+
+        grid[(1, 0)] = 'z';
+        assert_eq!(grid.row(0), Some(&['a', 'z', '.'][..]));
+        assert_eq!(Grid::<char>::new(0, 0, vec![]).unwrap().get_wrapping(1, 1), None);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = sample();
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [&['a', 'b', '.'][..], &['c', '.', 'd'][..]]);
+        assert_eq!(grid.column(1).collect::<String>(), "b.");
+        assert_eq!(grid.column(3).count(), 0);
+        assert_eq!(grid.columns().map(|column| column.collect::<String>()).collect::<Vec<_>>(), ["ac", "b.", ".d"]);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &'.')));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = sample();
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), [((1, 0), &'b'), ((0, 1), &'c')]);
+        assert_eq!(grid.neighbours4(1, 1).map(|(_, c)| *c).collect::<String>(), "bdc");
+        assert_eq!(grid.neighbours8(1, 0).map(|(_, c)| *c).collect::<String>(), "a.c.d");
+        assert_eq!(grid.neighbours8(2, 1).count(), 3);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = sample();
+        assert_eq!(grid.rotate_clockwise().to_string(), "ca\n.b\nd.");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), ".d\nb.\nac");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+        assert_eq!(grid.flip_horizontal().to_string(), ".ba\nd.c");
+        assert_eq!(grid.flip_vertical().to_string(), "c.d\nab.");
+        assert_eq!(grid.transpose().to_string(), "ac\nb.\n.d");
+        assert_eq!(grid.map(|c| c.is_alphabetic()).get(2, 1), Some(&true));
+    }
+}
@@ -0,0 +1,102 @@
+use std::{env, fs, io::{self, Read}, path::{Path, PathBuf}};
+
+use crate::RunError;
+
+/// Environment variable overriding the default `data` directory.
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+/// Where a day's puzzle input comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// `dayNN` in the data directory
+    DataDir,
+    Path(PathBuf),
+    Stdin,
+    Inline(String),
+}
+
+impl Source {
+    /// Build a source from the `--input` and `--input-str` arguments.
+    pub fn from_args(input: Option<PathBuf>, input_str: Option<String>) -> Source {
+        match (input, input_str) {
+            (_, Some(text)) => Source::Inline(text),
+            (Some(path), None) if path == Path::new("-") => Source::Stdin,
+            (Some(path), None) => Source::Path(path),
+            (None, None) => Source::DataDir,
+        }
+    }
+
+    /// Read the input with `\r\n` line endings normalised and the final
+    /// newline dropped, so parsers only ever see `\n` between lines.
+    pub fn load(&self, day: &str) -> Result<String, RunError> {
+        let data = match self {
+            Source::DataDir => read_file(&data_dir().join(day))?,
+            Source::Path(path) => read_file(path)?,
+            Source::Stdin => {
+                let mut data = String::new();
+                io::stdin().read_to_string(&mut data)
+                    .map_err(|source| RunError::IO { path: PathBuf::from("<stdin>"), source })?;
+                data
+            },
+            Source::Inline(text) => text.clone(),
+        };
+
+        let mut data = data.replace("\r\n", "\n");
+        if data.ends_with('\n') {
+            data.pop();
+        }
+
+        Ok(data)
+    }
+}
+
+pub fn data_dir() -> PathBuf {
+    env::var_os(DATA_DIR_VAR)
+        .map_or_else(|| PathBuf::from("data"), PathBuf::from)
+}
+
+fn read_file(path: &Path) -> Result<String, RunError> {
+    fs::read_to_string(path)
+        .map_err(|source| RunError::IO { path: path.to_path_buf(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        assert_eq!(Source::from_args(None, None), Source::DataDir);
+        assert_eq!(Source::from_args(Some("-".into()), None), Source::Stdin);
+        assert_eq!(
+            Source::from_args(Some("input.txt".into()), None),
+            Source::Path("input.txt".into()));
+        assert_eq!(
+            Source::from_args(None, Some("1\n2".to_string())),
+            Source::Inline("1\n2".to_string()));
+    }
+
+    #[test]
+    fn test_load_inline() {
+        assert_eq!(
+            Source::Inline("1\r\n2".to_string()).load("day01").unwrap(),
+            "1\n2");
+    }
+
+    #[test]
+    fn test_load_trailing_newline() {
+        let path = env::temp_dir().join(format!("aoc-input-{}", std::process::id()));
+        fs::write(&path, "1721\r\n979\r\n366\r\n").unwrap();
+        let loaded = Source::Path(path.clone()).load("day01");
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), "1721\n979\n366");
+        assert_eq!(Source::Inline("1\n2\n\n".to_string()).load("day01").unwrap(), "1\n2\n");
+    }
+
+    #[test]
+    fn test_load_missing_path() {
+        let error = Source::Path("no/such/file".into()).load("day01").unwrap_err();
+        assert!(error.to_string().contains("no/such/file"));
+    }
+}
@@ -1,69 +1,394 @@
+mod answers;
+mod bench;
+mod grid;
+mod input;
 mod puzzles;
 mod runerror;
+mod runner;
+mod scaffold;
+mod solver;
 
-use std::{fs, path};
-use clap::Parser;
+use std::{path::{Path, PathBuf}, process::ExitCode, rc::Rc};
+use answers::{Answers, Verdict};
+use bench::{BenchOptions, BenchReport};
+use clap::{ArgGroup, Args as ClapArgs, Parser, Subcommand};
+use input::Source;
 use runerror::RunError;
+use runner::{Format, RunResult};
 
 #[derive(Parser, Debug)]
+#[command(group(ArgGroup::new("select")
+    .required(true)
+    .args(["day", "days", "all", "list"])))]
+#[command(group(ArgGroup::new("source")
+    .args(["input", "input_str"])
+    .conflicts_with_all(["days", "all"])))]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     // Day's puzzle
     #[arg(short, long)]
-    day: u8,
+    day: Option<u8>,
+
+    // Days to run, e.g. "1-6,9"
+    #[arg(long)]
+    days: Option<String>,
+
+    // Run every implemented day
+    #[arg(short, long)]
+    all: bool,
 
     // Puzzle part
     #[arg(short, long, default_value_t = 0)]
     part: u8,
+
+    #[command(flatten)]
+    input: InputArgs,
+
+    // Output format for results
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    // List implemented puzzles
+    #[arg(short, long)]
+    list: bool,
+}
+
+#[derive(ClapArgs, Debug)]
+struct InputArgs {
+    // Input file for a single day, or "-" for stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    // Inline puzzle input for a single day
+    #[arg(long, conflicts_with = "input")]
+    input_str: Option<String>,
+}
+
+impl InputArgs {
+    fn source(self) -> Source {
+        Source::from_args(self.input, self.input_str)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Time parsing and each part of every selected day
+    Bench {
+        // Days to benchmark, e.g. "1-6,9"; defaults to all
+        #[arg(long)]
+        days: Option<String>,
+
+        // Timed runs per phase
+        #[arg(short = 'n', long, default_value_t = 100)]
+        iterations: usize,
+
+        // Untimed runs per phase before timing
+        #[arg(short, long, default_value_t = 10)]
+        warmup: usize,
+
+        // Write results to a JSON file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Check every selected day against the recorded answers
+    Verify {
+        // Days to verify, e.g. "1-6,9"; defaults to all
+        #[arg(long)]
+        days: Option<String>,
+
+        // Answers file; defaults to answers.toml in the data directory
+        #[arg(long)]
+        answers: Option<PathBuf>,
+
+        // Save the current answers as the new expectations
+        #[arg(short, long)]
+        record: bool,
+    },
+
+    /// Generate a new day from the puzzle skeleton
+    New {
+        // Day to create
+        #[arg(short, long)]
+        day: u8,
+
+        // Puzzle title
+        #[arg(short, long, default_value = "")]
+        name: String,
+
+        // Checkout to add the day to; sources go under src/puzzles, input under data
+        #[arg(short, long, default_value = ".")]
+        root: PathBuf,
+    },
+
+    /// Find any number of expense report entries summing to any target
+    Day01 {
+        #[command(flatten)]
+        options: puzzles::day01::Options,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+
+    /// Compare password validity under different policies
+    Day02 {
+        #[command(flatten)]
+        options: puzzles::day02::Options,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+
+    /// Search toboggan slopes for the fewest or most trees
+    Day03 {
+        #[command(flatten)]
+        options: puzzles::day03::Options,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+
+    /// Check passports against the puzzle rules or a custom schema
+    Day04 {
+        #[command(flatten)]
+        options: puzzles::day04::Options,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
 }
 
 struct ParsedArgs {
     day: String,
+    day_num: u8,
     part: u8,
+    source: Source,
+    format: Format,
 }
 
-fn main() -> Result<(), RunError> {
-    let parsed_args = parse_args()?;
-    let data = get_data(&parsed_args.day)?;
-    let parts: Vec<u8> = match parsed_args.part {
-        0 => [1, 2].to_vec(),
-        p => [p].to_vec()
-    };
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<ExitCode, RunError> {
+    match args.command {
+        Some(Command::Bench { days, iterations, warmup, output }) => {
+            let options = BenchOptions { warmup, iterations };
+            return run_bench(days.as_deref(), &options, output);
+        },
+        Some(Command::Verify { days, answers, record }) => {
+            let answers = answers.unwrap_or_else(|| input::data_dir().join("answers.toml"));
+            return run_verify(days.as_deref(), &answers, record);
+        },
+        Some(Command::New { day, name, root }) => {
+            for path in scaffold::new_day(&root, day, &name)? {
+                println!("wrote {}", path.display());
+            }
+            return Ok(ExitCode::SUCCESS);
+        },
+        Some(Command::Day01 { options, input }) => {
+            let data = input.source().load("day01")?;
+            println!("{}", puzzles::day01::explore(&data, &options)?);
+            return Ok(ExitCode::SUCCESS);
+        },
+        Some(Command::Day02 { options, input }) => {
+            let data = input.source().load("day02")?;
+            println!("{}", puzzles::day02::explore(&data, &options)?);
+            return Ok(ExitCode::SUCCESS);
+        },
+        Some(Command::Day03 { options, input }) => {
+            let data = if options.needs_input() { input.source().load("day03")? } else { String::new() };
+            println!("{}", puzzles::day03::explore(&data, &options)?);
+            return Ok(ExitCode::SUCCESS);
+        },
+        Some(Command::Day04 { options, input }) => {
+            let data = input.source().load("day04")?;
+            println!("{}", puzzles::day04::explore(&data, &options)?);
+            return Ok(ExitCode::SUCCESS);
+        },
+        None => {}
+    }
+
+    if args.list {
+        list_puzzles();
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if args.all || args.days.is_some() {
+        return run_days(args);
+    }
+
+    let parsed_args = parse_args(args)?;
+    let puzzle = puzzles::find(parsed_args.day_num)
+        .ok_or_else(|| RunError::NotImplemented(parsed_args.day.clone()))?;
+    if parsed_args.format != Format::Text {
+        let results = run_selected(vec![parsed_args.day_num], parsed_args.part, &parsed_args.source);
+        return report(&results, parsed_args.format);
+    }
 
-    for part in parts {
+    let data = parsed_args.source.load(&parsed_args.day)?;
 
-        let result = match &*parsed_args.day {
-            "day01" => {puzzles::day01::main(part, &data)?},
-            "day02" => {puzzles::day02::main(part, &data)?},
-            "day03" => {puzzles::day03::main(part, &data)?},
-            "day04" => {puzzles::day04::main(part, &data)?},
-            "day05" => {puzzles::day05::main(part, &data)?},
-            "day06" => {puzzles::day06::main(part, &data)?},
-            _ => {return Err(RunError::NotImplemented(parsed_args.day));}
-        };
+    for part in parts(parsed_args.part) {
+        let result = puzzle.run(part, &data)?;
 
         println!("{} part {}:\n{}",
         parsed_args.day, part, result)
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
 
-fn parse_args() -> Result<ParsedArgs, RunError> {
-    let args = Args::parse();
+fn list_puzzles() {
+    for puzzle in puzzles::REGISTRY {
+        println!("{} day{:02}: {}",
+        puzzle.year(), puzzle.day(), puzzle.name())
+    }
+}
 
+fn run_days(args: Args) -> Result<ExitCode, RunError> {
     if args.part > 2 {
         return Err(RunError::BadPartNum);
     }
 
-    let day = format!("day{:02}", args.day);
+    let results = run_selected(select_days(args.days.as_deref())?, args.part, &Source::DataDir);
+    report(&results, args.format)
+}
+
+fn report(results: &[RunResult], format: Format) -> Result<ExitCode, RunError> {
+    runner::print_results(results, format)?;
+
+    if results.iter().all(|result| result.answer.is_ok()) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn run_selected(days: Vec<u8>, part: u8, source: &Source) -> Vec<RunResult> {
+    let mut results: Vec<RunResult> = vec![];
+    for day_num in days {
+        let day = format!("day{:02}", day_num);
+        let loaded = puzzles::find(day_num)
+            .ok_or_else(|| RunError::NotImplemented(day.clone()))
+            .and_then(|puzzle| Ok((puzzle, source.load(&day)?)));
 
-    Ok(ParsedArgs {day, part: args.part})
+        match loaded {
+            Ok((puzzle, data)) => {
+                for part in parts(part) {
+                    results.push(runner::run_part(puzzle, part, &data));
+                }
+            },
+            Err(e) => {
+                let e = Rc::new(e);
+                let year = puzzles::find(day_num).map(|puzzle| puzzle.year());
+                for part in parts(part) {
+                    results.push(RunResult::failed(year, day_num, part, Rc::clone(&e)));
+                }
+            }
+        }
+    }
+
+    results
 }
 
-fn get_data(day: &str) -> Result<String, RunError> {
-    let data_path = path::Path::new("data").join(day);
-    match fs::read_to_string(data_path) {
-        Ok(data) => Ok(data.replace("\r\n", "\n")),
-        Err(e) => Err(RunError::IO(e))
+fn run_verify(days: Option<&str>, path: &Path, record: bool) -> Result<ExitCode, RunError> {
+    let mut answers = Answers::load(path)?;
+    let results = run_selected(select_days(days)?, 0, &Source::DataDir);
+
+    if record {
+        for result in &results {
+            if let Ok(answer) = &result.answer {
+                answers.record(result.day, result.part, answer);
+            }
+        }
+        answers.save()?;
+    }
+
+    answers::print_table(&answers, &results);
+
+    if results.iter().all(|result|
+        matches!(answers.verdict(result), Verdict::Pass | Verdict::Missing)) {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+fn run_bench(days: Option<&str>, options: &BenchOptions, output: Option<PathBuf>) -> Result<ExitCode, RunError> {
+    let mut reports: Vec<BenchReport> = vec![];
+    let mut failed = false;
+
+    for day_num in select_days(days)? {
+        let day = format!("day{:02}", day_num);
+        let report = puzzles::find(day_num)
+            .ok_or_else(|| RunError::NotImplemented(day.clone()))
+            .and_then(|puzzle| puzzle.bench(&Source::DataDir.load(&day)?, options));
+
+        match report {
+            Ok(report) => reports.push(report),
+            Err(e) => {
+                eprintln!("{}: {}", day, e);
+                failed = true;
+            }
+        }
+    }
+
+    bench::print_table(&reports);
+
+    if let Some(path) = output {
+        bench::write_json(&path, options, &reports)?;
+    }
+
+    if failed {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+fn select_days(spec: Option<&str>) -> Result<Vec<u8>, RunError> {
+    match spec {
+        Some(spec) => runner::parse_days(spec),
+        None => Ok(puzzles::REGISTRY.iter().map(|puzzle| puzzle.day()).collect()),
+    }
+}
+
+fn parts(part: u8) -> Vec<u8> {
+    match part {
+        0 => [1, 2].to_vec(),
+        p => [p].to_vec()
+    }
+}
+
+fn parse_args(args: Args) -> Result<ParsedArgs, RunError> {
+    if args.part > 2 {
+        return Err(RunError::BadPartNum);
+    }
+
+    let day_num = args.day.ok_or(RunError::BadDayNum)?;
+    let day = format!("day{:02}", day_num);
+
+    let source = args.input.source();
+
+    Ok(ParsedArgs {day, day_num, part: args.part, source, format: args.format})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_args() {
+        Args::command().debug_assert();
     }
 }
@@ -1,56 +1,218 @@
-use crate::RunError;
+use std::cmp::Ordering;
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+use num_bigint::BigInt;
 
-    match part {
-        1 => part1(parsed_data),
-        2 => part2(parsed_data),
-        _ => Err(RunError::BadPartNum)
+use crate::{RunError, runerror::ParseError, solver::{Answer, Solver}};
+
+const DAY: u8 = 1;
+
+pub struct Day01;
+
+impl Solver for Day01 {
+    type Input<'a> = Vec<usize>;
+
+    fn name(&self) -> &'static str {
+        "Report Repair"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
     }
 }
 
 fn parse_data(data: &str) -> Result<Vec<usize>, RunError> {
     let lines: Vec<&str> = data[..].split('\n').collect();
 
-    match lines.iter()
-        .map(|x| x.trim().parse::<usize>())
-        .collect() {
-            Ok(parsed_data) => Ok(parsed_data),
-            Err(e) => Err(RunError::ParseInt(e))
-        }
+    lines.iter()
+        .map(|x| {
+            let value = x.trim();
+            value.parse::<usize>()
+                .map_err(|_| ParseError::at(DAY, data, value, "a positive integer").into())
+        })
+        .collect()
 }
 
-fn part1(values: Vec<usize>) -> Result<usize, RunError> {
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    // Value the entries must sum to
+    #[arg(short, long, default_value_t = 2020)]
+    target: usize,
+
+    // Number of entries to combine
+    #[arg(short, long, default_value_t = 2)]
+    k: usize,
+
+    // List every matching combination instead of the first
+    #[arg(short, long)]
+    all: bool,
+
+    // Compute products with arbitrary precision instead of failing on overflow
+    #[arg(short, long)]
+    big: bool,
+}
+
+fn part1(values: &[usize]) -> Result<usize, RunError> {
     // Find two entries that sum to 2020 and return their product.
-    for i in values.iter() {
-        for j in values.iter() {
-            if i != j && *i + *j == 2020 {
-                return Ok(i * j);
-            }
-        }
-    }
+    let entries = find_k_sum(values, 2, 2020).ok_or(RunError::PartFailed)?;
 
-    println!("No pairs found!");
-    Err(RunError::PartFailed)
+    checked_product(&entries)
 }
 
-fn part2(values: Vec<usize>) -> Result<usize, RunError> {
+fn part2(values: &[usize]) -> Result<usize, RunError> {
     // Find three entries that sum to 2020 and return their product.
-    for i in values.iter() {
-        for j in values.iter() {
-            if i != j {
-                for k in values.iter() {
-                    if k != j && *i + *j + *k == 2020 {
-                        return Ok(i * j * k);
-                    }
+    let entries = find_k_sum(values, 3, 2020).ok_or(RunError::PartFailed)?;
+
+    checked_product(&entries)
+}
+
+fn checked_product(entries: &[usize]) -> Result<usize, RunError> {
+    entries.iter()
+        .try_fold(1usize, |product, &entry| product.checked_mul(entry))
+        .ok_or_else(|| RunError::Overflow(format!("product of {:?}", entries)))
+}
+
+fn big_product(entries: &[usize]) -> BigInt {
+    entries.iter().map(|&entry| BigInt::from(entry)).product()
+}
+
+/// Find `k` distinct entries summing to `target`, returned in ascending order.
+pub fn find_k_sum(values: &[usize], k: usize, target: usize) -> Option<Vec<usize>> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+
+    k_sum_sorted(&sorted, k, target)
+}
+
+fn k_sum_sorted(sorted: &[usize], k: usize, target: usize) -> Option<Vec<usize>> {
+    match k {
+        0 => (target == 0).then(Vec::new),
+        1 => sorted.binary_search(&target).ok().map(|_| vec![target]),
+        2 => {
+            // Two pointers closing in from either end
+            let (mut lo, mut hi) = (0, sorted.len().checked_sub(1)?);
+            while lo < hi {
+                // An overflowing sum is certainly past the target
+                let sum = sorted[lo].checked_add(sorted[hi]);
+                match sum.map_or(Ordering::Greater, |sum| sum.cmp(&target)) {
+                    Ordering::Less => lo += 1,
+                    Ordering::Greater => hi -= 1,
+                    Ordering::Equal => return Some(vec![sorted[lo], sorted[hi]]),
                 }
             }
+            None
+        },
+        _ => {
+            // Fix the smallest entry and solve for k - 1 in the rest
+            for (i, &first) in sorted.iter().enumerate() {
+                if first > target {
+                    break;
+                }
+                if i > 0 && sorted[i - 1] == first {
+                    continue;
+                }
+                if let Some(mut rest) = k_sum_sorted(&sorted[i + 1..], k - 1, target - first) {
+                    rest.insert(0, first);
+                    return Some(rest);
+                }
+            }
+            None
         }
     }
+}
+
+/// Every set of `k` entries summing to `target`, as ascending entry indices.
+///
+/// Entries are matched by position, so equal values at different indices
+/// are separate entries. Combinations are in lexicographic index order.
+pub fn k_sum_combinations(values: &[usize], k: usize, target: usize) -> Vec<Vec<usize>> {
+    // Search in value order so we can stop once the sum overshoots
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|&i| values[i]);
+
+    let mut combinations: Vec<Vec<usize>> = vec![];
+    let mut chosen: Vec<usize> = Vec::with_capacity(k);
+    collect_combinations(values, &order, k, target, &mut chosen, &mut combinations);
 
-    println!("No triples found!");
-    Err(RunError::PartFailed)
+    for combination in combinations.iter_mut() {
+        combination.sort_unstable();
+    }
+    combinations.sort();
+
+    combinations
+}
+
+fn collect_combinations(
+    values: &[usize],
+    order: &[usize],
+    k: usize,
+    target: usize,
+    chosen: &mut Vec<usize>,
+    combinations: &mut Vec<Vec<usize>>,
+) {
+    if k == 0 {
+        if target == 0 {
+            combinations.push(chosen.clone());
+        }
+        return;
+    }
+
+    for (position, &index) in order.iter().enumerate() {
+        if order.len() - position < k || values[index] > target {
+            break;
+        }
+
+        chosen.push(index);
+        collect_combinations(values, &order[position + 1..], k - 1, target - values[index], chosen, combinations);
+        chosen.pop();
+    }
+}
+
+fn describe(entries: &[usize], target: usize, big: bool) -> Result<String, RunError> {
+    let product = if big {
+        Answer::from(big_product(entries))
+    } else {
+        Answer::from(checked_product(entries)?)
+    };
+
+    Ok(format!("{} = {}, product {}",
+        entries.iter().map(usize::to_string).collect::<Vec<String>>().join(" + "),
+        target,
+        product))
+}
+
+/// Solve the expense report for an arbitrary target and entry count.
+pub fn explore(data: &str, options: &Options) -> Result<String, RunError> {
+    let values = parse_data(data)?;
+
+    if options.all {
+        let combinations = k_sum_combinations(&values, options.k, options.target);
+        let mut report = format!("{} combinations of {} entries sum to {}",
+            combinations.len(), options.k, options.target);
+
+        for indices in combinations {
+            let entries: Vec<usize> = indices.iter().map(|&i| values[i]).collect();
+            report += &format!("\n{:?} {}", indices, describe(&entries, options.target, options.big)?);
+        }
+
+        return Ok(report);
+    }
+
+    Ok(match find_k_sum(&values, options.k, options.target) {
+        Some(entries) => describe(&entries, options.target, options.big)?,
+        None => format!("No {} entries sum to {}", options.k, options.target),
+    })
 }
 
 #[cfg(test)]
@@ -64,7 +226,7 @@ mod tests {
     675
     1456";
 
-    static SAMPLE_DATA: &'static [usize] = &[1721,
+    static SAMPLE_DATA: &[usize] = &[1721,
     979,
     366,
     299,
@@ -79,11 +241,60 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(SAMPLE_DATA.to_vec()).unwrap(), 514579);
+        assert_eq!(part1(SAMPLE_DATA).unwrap(), 514579);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(SAMPLE_DATA.to_vec()).unwrap(), 241861950);
+        assert_eq!(part2(SAMPLE_DATA).unwrap(), 241861950);
+    }
+
+    #[test]
+    fn test_find_k_sum() {
+        assert_eq!(find_k_sum(SAMPLE_DATA, 2, 2020).unwrap(), [299, 1721]);
+        assert_eq!(find_k_sum(SAMPLE_DATA, 3, 2020).unwrap(), [366, 675, 979]);
+        assert_eq!(find_k_sum(SAMPLE_DATA, 1, 979).unwrap(), [979]);
+        assert_eq!(find_k_sum(SAMPLE_DATA, 4, 979 + 366 + 299 + 675).unwrap(), [299, 366, 675, 979]);
+        assert_eq!(find_k_sum(SAMPLE_DATA, 0, 0).unwrap(), Vec::<usize>::new());
+        assert_eq!(find_k_sum(SAMPLE_DATA, 2, 1), None);
+        assert_eq!(find_k_sum(SAMPLE_DATA, 7, 2020), None);
+        assert_eq!(find_k_sum(&[], 2, 2020), None);
+    }
+
+    #[test]
+    fn test_k_sum_combinations() {
+        assert_eq!(k_sum_combinations(SAMPLE_DATA, 2, 2020), [[0, 3]]);
+        assert_eq!(k_sum_combinations(SAMPLE_DATA, 3, 2020), [[1, 2, 4]]);
+        assert_eq!(
+            k_sum_combinations(&[1010, 5, 1010, 1010], 2, 2020),
+            [[0, 2], [0, 3], [2, 3]]);
+        assert_eq!(k_sum_combinations(&[1, 3, 2, 4, 0], 2, 4), [[0, 1], [3, 4]]);
+        assert!(k_sum_combinations(SAMPLE_DATA, 2, 1).is_empty());
+        assert!(k_sum_combinations(SAMPLE_DATA, 7, 2020).is_empty());
+    }
+
+    #[test]
+    fn test_find_k_sum_overflow() {
+        let values = [usize::MAX, 1, usize::MAX - 1];
+        assert_eq!(find_k_sum(&values, 2, usize::MAX).unwrap(), [1, usize::MAX - 1]);
+        assert_eq!(find_k_sum(&values, 3, usize::MAX), None);
+    }
+
+    #[test]
+    fn test_product_overflow() {
+        assert_eq!(checked_product(&[299, 1721]).unwrap(), 514579);
+        assert!(matches!(checked_product(&[usize::MAX, 2]), Err(RunError::Overflow(_))));
+        assert_eq!(big_product(&[usize::MAX, 2]), BigInt::from(usize::MAX) * 2);
+
+        let entries = [usize::MAX / 2, 4];
+        assert!(describe(&entries, 0, false).is_err());
+        assert!(describe(&entries, 0, true).unwrap()
+            .ends_with(&(BigInt::from(usize::MAX / 2) * BigInt::from(4)).to_string()));
+    }
+
+    #[test]
+    fn test_find_k_sum_uses_each_entry_once() {
+        assert_eq!(find_k_sum(&[1010, 5], 2, 2020), None);
+        assert_eq!(find_k_sum(&[1010, 5, 1010], 2, 2020).unwrap(), [1010, 1010]);
     }
 }
@@ -1,88 +1,471 @@
-use crate::RunError;
+use std::{collections::HashMap, fmt};
+
+use regex::Regex;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{RunError, runerror::ParseError, solver::{Answer, Solver}};
 
 #[derive(Debug, Clone, PartialEq)]
-struct Password <'a> {
+pub struct Password <'a> {
     min: usize,
     max: usize,
     character: char,
     password: &'a str
 }
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+impl fmt::Display for Password<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{} {}: {}", self.min, self.max, self.character, self.password)
+    }
+}
+
+const DAY: u8 = 2;
+
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input<'a> = Vec<Password<'a>>;
+
+    fn name(&self) -> &'static str {
+        "Password Philosophy"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
 
-    match part {
-        1 => part1(&parsed_data),
-        2 => part2(&parsed_data),
-        _ => Err(RunError::BadPartNum)
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
     }
 }
 
 fn parse_data<'a>(data: &'a str) -> Result<Vec<Password <'a>>, RunError> {
-    let lines: Vec<&str> = data.split('\n').collect();
-
-    // sample line:
-    // 1-3 a: abcde
-
-    let mut passwords: Vec<Password <'a>> = vec![];
-    for line in lines {
-        let parts: Vec<&str> = line.split(' ').collect();
-        let min_max: Vec<&str> = parts[0].split('-').collect();
-        let character: char = match parts[1].chars().next() {
-            Some(c) => c,
-            None => {return Err(RunError::ParseString(parts[1].to_string()));}
-        };
+    parse_records(data).into_iter()
+        .map(|record| record.map_err(RunError::from))
+        .collect()
+}
 
-        passwords.push(Password {
-            min: min_max[0].parse::<usize>()?,
-            max: min_max[1].parse::<usize>()?,
-            character,
-            password: parts[2]
-        });
+/// Parse every line independently, so one bad record doesn't hide the rest.
+fn parse_records(data: &str) -> Vec<Result<Password<'_>, ParseError>> {
+    // A final newline doesn't start another record
+    let records = data.strip_suffix('\n').unwrap_or(data);
+
+    records.split('\n')
+        .map(|line| parse_record(data, line))
+        .collect()
+}
+
+/// Walks a single record, pointing errors at the offending character.
+struct Cursor<'a> {
+    data: &'a str,
+    line: &'a str,
+    position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn rest(&self) -> &'a str {
+        &self.line[self.position..]
     }
 
-    Ok(passwords)
+    fn error(&self, expected: &str) -> ParseError {
+        ParseError::at(DAY, self.data, self.rest(), expected)
+    }
+
+    fn literal(&mut self, literal: &str) -> Result<(), ParseError> {
+        if !self.rest().starts_with(literal) {
+            return Err(self.error(&format!("{:?}", literal)));
+        }
 
+        self.position += literal.len();
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        let digits = self.rest().find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        let value = self.rest()[..digits].parse::<usize>()
+            .map_err(|_| self.error("a positive integer"))?;
+
+        self.position += digits;
+        Ok(value)
+    }
+
+    fn character(&mut self) -> Result<char, ParseError> {
+        let c = self.rest().chars().next()
+            .filter(|c| !c.is_whitespace() && *c != ':')
+            .ok_or_else(|| self.error("a policy character"))?;
+
+        self.position += c.len_utf8();
+        Ok(c)
+    }
+
+    fn word(&mut self) -> Result<&'a str, ParseError> {
+        let rest = self.rest();
+        if rest.is_empty() || rest.contains(char::is_whitespace) {
+            return Err(self.error("a password without spaces"));
+        }
+
+        self.position = self.line.len();
+        Ok(rest)
+    }
+}
+
+fn parse_record<'a>(data: &'a str, line: &'a str) -> Result<Password<'a>, ParseError> {
+    // record := min '-' max ' ' character ': ' password
+    // e.g. 1-3 a: abcde
+    let mut cursor = Cursor { data, line, position: 0 };
+
+    let min = cursor.number()?;
+    cursor.literal("-")?;
+    let max = cursor.number()?;
+    cursor.literal(" ")?;
+    let character = cursor.character()?;
+    cursor.literal(": ")?;
+    let password = cursor.word()?;
+
+    Ok(Password { min, max, character, password })
 }
 
 fn part1(values: &[Password]) -> Result<usize, RunError> {
     // Count valid passwords, given allowed counts of a specified character
 
+    count_valid(values, &CountPolicy)
+}
+
+fn part2(values: &[Password]) -> Result<usize, RunError> {
+    // Check given positions for a given character
+
+    count_valid(values, &PositionPolicy::Chars)
+}
+
+fn count_valid(values: &[Password], policy: &dyn PasswordPolicy) -> Result<usize, RunError> {
     let mut valid_count: usize = 0;
 
     for value in values {
+        if policy.is_valid(value)? {
+            valid_count += 1;
+        }
+    }
+
+    Ok(valid_count)
+}
+
+/// Whether a record passes a policy, and why.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verdict {
+    pub valid: bool,
+    pub reason: String,
+}
+
+impl Verdict {
+    fn new(valid: bool, reason: String) -> Verdict {
+        Verdict { valid, reason }
+    }
+}
+
+/// A rule a password record can be checked against.
+pub trait PasswordPolicy {
+    fn name(&self) -> String;
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError>;
+
+    fn is_valid(&self, value: &Password) -> Result<bool, RunError> {
+        Ok(self.check(value)?.valid)
+    }
+}
+
+/// The record's character must appear between min and max times.
+pub struct CountPolicy;
+
+impl PasswordPolicy for CountPolicy {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
         let character_count = value.password.chars()
             .filter(|character| *character == value.character)
             .count();
-        if character_count >= value.min && character_count <= value.max {
-            valid_count += 1;
+
+        Ok(Verdict::new(
+            character_count >= value.min && character_count <= value.max,
+            format!("found {} of '{}', need {}–{}",
+                character_count, value.character, value.min, value.max)))
+    }
+}
+
+/// Exactly one of the record's two 1-based positions holds its character.
+///
+/// Positions count chars, or user-perceived grapheme clusters.
+pub enum PositionPolicy {
+    Chars,
+    Graphemes,
+}
+
+impl PositionPolicy {
+    fn units<'a>(&self, password: &'a str) -> Vec<&'a str> {
+        match self {
+            PositionPolicy::Chars => password.char_indices()
+                .map(|(i, c)| &password[i..i + c.len_utf8()])
+                .collect(),
+            PositionPolicy::Graphemes => password.graphemes(true).collect(),
         }
     }
+}
 
-    Ok(valid_count)
+impl PasswordPolicy for PositionPolicy {
+    fn name(&self) -> String {
+        match self {
+            PositionPolicy::Chars => "position".to_string(),
+            PositionPolicy::Graphemes => "position=graphemes".to_string(),
+        }
+    }
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
+        let units = self.units(value.password);
+        let at = |position: usize| match position {
+            0 => Err(RunError::ZeroPosition(value.to_string())),
+            _ => units.get(position - 1).copied().ok_or_else(|| RunError::PositionBounds {
+                position,
+                length: units.len(),
+                password: value.password.to_string(),
+            }),
+        };
+        let first = at(value.min)?;
+        let last = at(value.max)?;
+
+        let c = value.character.to_string();
+        Ok(match (first == c, last == c) {
+            (true, true) => Verdict::new(false,
+                format!("positions {} and {} are both '{}'", value.min, value.max, c)),
+            (false, false) => Verdict::new(false,
+                format!("neither position {} ('{}') nor {} ('{}') is '{}'",
+                    value.min, first, value.max, last, c)),
+            (true, false) => Verdict::new(true,
+                format!("only position {} is '{}'", value.min, c)),
+            (false, true) => Verdict::new(true,
+                format!("only position {} is '{}'", value.max, c)),
+        })
+    }
 }
 
-fn part2(values: &[Password]) -> Result<usize, RunError> {
-    // Check given positions for a given character
+/// The whole password must match a regular expression.
+pub struct RegexPolicy(Regex);
 
-    let mut valid_count: usize = 0;
+impl PasswordPolicy for RegexPolicy {
+    fn name(&self) -> String {
+        format!("regex={}", self.0.as_str())
+    }
 
-    for value in values {
-        if value.password.len() < value.max {
-            return Err(RunError::InputBounds);
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
+        Ok(if self.0.is_match(value.password) {
+            Verdict::new(true, format!("matches /{}/", self.0.as_str()))
+        } else {
+            Verdict::new(false, format!("does not match /{}/", self.0.as_str()))
+        })
+    }
+}
+
+/// None of the given characters may appear.
+pub struct ForbiddenPolicy(Vec<char>);
+
+impl PasswordPolicy for ForbiddenPolicy {
+    fn name(&self) -> String {
+        format!("forbidden={}", self.0.iter().collect::<String>())
+    }
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
+        Ok(match value.password.chars().find(|c| self.0.contains(c)) {
+            Some(c) => Verdict::new(false, format!("contains forbidden '{}'", c)),
+            None => Verdict::new(true, "no forbidden characters".to_string()),
+        })
+    }
+}
+
+/// Total Shannon entropy of the password, in bits, must reach a minimum.
+pub struct EntropyPolicy(f64);
+
+impl PasswordPolicy for EntropyPolicy {
+    fn name(&self) -> String {
+        format!("entropy={}", self.0)
+    }
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
+        let bits = entropy(value.password);
+
+        Ok(Verdict::new(bits >= self.0, format!("{:.1} bits, need {}", bits, self.0)))
+    }
+}
+
+fn entropy(password: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in password.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+
+    let length = password.chars().count() as f64;
+    let bits_per_char: f64 = counts.values()
+        .map(|&count| {
+            let p = count as f64 / length;
+            -p * p.log2()
+        })
+        .sum();
+
+    bits_per_char * length
+}
+
+/// No character may repeat consecutively more than a given number of times.
+pub struct MaxRunPolicy(usize);
+
+impl PasswordPolicy for MaxRunPolicy {
+    fn name(&self) -> String {
+        format!("max-run={}", self.0)
+    }
+
+    fn check(&self, value: &Password) -> Result<Verdict, RunError> {
+        let run = longest_run(value.password);
+
+        Ok(Verdict::new(run <= self.0, format!("longest run is {}, max {}", run, self.0)))
+    }
+}
+
+fn longest_run(password: &str) -> usize {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<char> = None;
+
+    for c in password.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        previous = Some(c);
+    }
+
+    longest
+}
+
+/// Build a policy from a CLI spec such as `count` or `regex=^[a-z]+$`.
+pub fn parse_policy(spec: &str) -> Result<Box<dyn PasswordPolicy>, RunError> {
+    let bad_policy = || RunError::BadPolicy(spec.to_string());
+    let (name, arg) = match spec.split_once('=') {
+        Some((name, arg)) => (name, Some(arg)),
+        None => (spec, None),
+    };
+
+    Ok(match (name, arg) {
+        ("count", None) => Box::new(CountPolicy),
+        ("position", None | Some("chars")) => Box::new(PositionPolicy::Chars),
+        ("position", Some("graphemes")) => Box::new(PositionPolicy::Graphemes),
+        ("regex", Some(pattern)) => Box::new(RegexPolicy(
+            Regex::new(pattern).map_err(|e| RunError::Regex(e.to_string()))?)),
+        ("forbidden", Some(chars)) => Box::new(ForbiddenPolicy(chars.chars().collect())),
+        ("entropy", Some(bits)) => Box::new(EntropyPolicy(bits.parse().map_err(|_| bad_policy())?)),
+        ("max-run", Some(run)) => Box::new(MaxRunPolicy(run.parse().map_err(|_| bad_policy())?)),
+        _ => return Err(bad_policy()),
+    })
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    // Policies to compare: count, position[=chars|graphemes], regex=PATTERN,
+    // forbidden=CHARS, entropy=BITS, max-run=N
+    #[arg(short = 'P', long = "policy", default_values = ["count", "position"])]
+    policies: Vec<String>,
+
+    // List every record with its verdict and reason
+    #[arg(short, long)]
+    report: bool,
+
+    // Only list records that fail a policy
+    #[arg(long, requires = "report")]
+    invalid_only: bool,
+
+    // Warn about malformed records and carry on without them
+    #[arg(short, long)]
+    skip_invalid: bool,
+}
+
+/// Validate the password database under each policy, side by side.
+pub fn explore(data: &str, options: &Options) -> Result<String, RunError> {
+    let mut records: Vec<(usize, Password)> = vec![];
+    for (line, record) in parse_records(data).into_iter().enumerate() {
+        match record {
+            Ok(value) => records.push((line + 1, value)),
+            Err(e) if options.skip_invalid => eprintln!("Warning: skipping {}", e),
+            Err(e) => return Err(e.into()),
         }
+    }
 
-        let first = value.password.get(value.min - 1..value.min)
-            .ok_or(RunError::PartFailed)?.chars().next().ok_or(RunError::PartFailed)?;
-        let last = value.password.get(value.max - 1..value.max)
-            .ok_or(RunError::PartFailed)?.chars().next().ok_or(RunError::PartFailed)?;
-        if  first != last && (first == value.character || last == value.character ) {
-            valid_count += 1;
+    let policies = options.policies.iter()
+        .map(|spec| parse_policy(spec))
+        .collect::<Result<Vec<Box<dyn PasswordPolicy>>, RunError>>()?;
+
+    if options.report {
+        return report(&records, &policies, options.invalid_only);
+    }
+
+    let mut report = format!("{:<24} {:>8} {:>8}", "policy", "valid", "invalid");
+    for policy in policies {
+        let mut valid = 0;
+        for (line, value) in &records {
+            match policy.check(value) {
+                Ok(verdict) => valid += usize::from(verdict.valid),
+                Err(e) if unusable(&e) =>
+                    eprintln!("Warning: line {} is invalid under {}: {}", line, policy.name(), e),
+                Err(e) => return Err(e),
+            }
         }
+        report += &format!("\n{:<24} {:>8} {:>8}", policy.name(), valid, records.len() - valid);
     }
 
-    Ok(valid_count)
+    Ok(report)
+}
 
+/// A record the policy can't be applied to, which exploring lists as invalid
+/// rather than giving up on the whole database.
+fn unusable(e: &RunError) -> bool {
+    matches!(e, RunError::PositionBounds { .. } | RunError::ZeroPosition(_))
+}
+
+/// One block per record: the line, then each policy's verdict and reason.
+fn report(records: &[(usize, Password)], policies: &[Box<dyn PasswordPolicy>], invalid_only: bool) -> Result<String, RunError> {
+    let width = policies.iter().map(|policy| policy.name().len()).max().unwrap_or(0);
+    let mut blocks: Vec<String> = vec![];
+
+    for (line, value) in records {
+        let mut block = format!("{}: {}", line, value);
+        let mut shown = false;
+
+        for policy in policies {
+            let verdict = match policy.check(value) {
+                Ok(verdict) => verdict,
+                Err(e) if unusable(&e) => Verdict::new(false, e.to_string()),
+                Err(e) => return Err(e),
+            };
+            if invalid_only && verdict.valid {
+                continue;
+            }
+
+            block += &format!("\n    {:<width$}  {:<7}  {}",
+                policy.name(),
+                if verdict.valid { "valid" } else { "INVALID" },
+                verdict.reason);
+            shown = true;
+        }
+
+        if shown {
+            blocks.push(block);
+        }
+    }
+
+    Ok(blocks.join("\n"))
 }
 
 #[cfg(test)]
@@ -103,17 +486,211 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        assert!(parse_data(&SAMPLE_INPUT.to_string()).unwrap()
+        assert!(parse_data(SAMPLE_INPUT).unwrap()
             .iter().all(|item| SAMPLE_DATA.contains(item)));
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let error = match parse_data("1-3 a: abcde\n1-x b: cdefg").unwrap_err() {
+            RunError::Parse(error) => error,
+            e => panic!("unexpected error {e}"),
+        };
+        assert_eq!(*error, ParseError {
+            day: 2,
+            line: 2,
+            column: 3,
+            text: "1-x b: cdefg".to_string(),
+            expected: "a positive integer".to_string(),
+        });
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let expect = |input: &str, column: usize, expected: &str| {
+            let error = match parse_data(input).unwrap_err() {
+                RunError::Parse(error) => error,
+                e => panic!("unexpected error {e}"),
+            };
+            assert_eq!((error.line, error.column, error.expected.as_str()), (1, column, expected), "{input:?}");
+        };
+
+        expect("", 1, "a positive integer");
+        expect("1 3 a: abc", 2, "\"-\"");
+        expect("1-3a: abc", 4, "\" \"");
+        expect("1-3 : abc", 5, "a policy character");
+        expect("1-3 a abc", 6, "\": \"");
+        expect("1-3 a: ", 8, "a password without spaces");
+        expect("1-3 a: ab c", 8, "a password without spaces");
+        expect("-3 a: abc", 1, "a positive integer");
+    }
+
+    #[test]
+    fn test_parse_trailing_newline() {
+        assert_eq!(parse_data("1-3 a: abcde\n").unwrap(), &SAMPLE_DATA[..1]);
+        assert!(parse_data("1-3 a: abcde\n\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_records() {
+        let records = parse_records("1-3 a: abcde\n1-3 b\n2-9 c: ccccccccc");
+        assert_eq!(records[0], Ok(SAMPLE_DATA[0].clone()));
+        assert_eq!(records[1].as_ref().unwrap_err().line, 2);
+        assert_eq!(records[2], Ok(SAMPLE_DATA[2].clone()));
+    }
+
+    #[test]
+    fn test_explore_skip_invalid() {
+        let mut options = Options {
+            policies: vec!["count".to_string()],
+            report: false,
+            invalid_only: false,
+            skip_invalid: false,
+        };
+        let input = "1-3 a: abcde\n1-3 b\n2-9 c: ccccccccc";
+        assert!(explore(input, &options).is_err());
+
+        options.skip_invalid = true;
+        assert_eq!(explore(input, &options).unwrap(), "\
+policy                      valid  invalid
+count                           2        0");
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&SAMPLE_DATA.to_vec()).unwrap(), SAMPLE_GOALS[0]);
+        assert_eq!(part1(&SAMPLE_DATA).unwrap(), SAMPLE_GOALS[0]);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&SAMPLE_DATA.to_vec()).unwrap(), SAMPLE_GOALS[1]);
+        assert_eq!(part2(&SAMPLE_DATA).unwrap(), SAMPLE_GOALS[1]);
+    }
+
+    fn valid_under(spec: &str) -> Vec<bool> {
+        let policy = parse_policy(spec).unwrap();
+        SAMPLE_DATA.iter().map(|value| policy.is_valid(value).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_policies() {
+        assert_eq!(valid_under("count"), [true, false, true]);
+        assert_eq!(valid_under("position"), [true, false, false]);
+        assert_eq!(valid_under("regex=^[a-e]+$"), [true, false, true]);
+        assert_eq!(valid_under("forbidden=ab"), [false, true, true]);
+        assert_eq!(valid_under("entropy=10"), [true, true, false]);
+        assert_eq!(valid_under("max-run=1"), [true, true, false]);
+        assert_eq!(valid_under("max-run=9"), [true, true, true]);
+    }
+
+    #[test]
+    fn test_parse_policy_invalid() {
+        assert!(parse_policy("bogus").is_err());
+        assert!(parse_policy("count=3").is_err());
+        assert!(parse_policy("entropy=lots").is_err());
+        assert!(parse_policy("regex=(").is_err());
+    }
+
+    #[test]
+    fn test_explore() {
+        let options = Options {
+            policies: vec!["count".to_string(), "position".to_string()],
+            report: false,
+            invalid_only: false,
+            skip_invalid: false,
+        };
+        assert_eq!(explore(SAMPLE_INPUT, &options).unwrap(), "\
+policy                      valid  invalid
+count                           2        1
+position                        1        2");
+
+        let unusable = "1-3 a: abcde\n1-9 a: abc\n0-2 b: bb";
+        assert_eq!(explore(unusable, &options).unwrap(), "\
+policy                      valid  invalid
+count                           3        0
+position                        1        2");
+    }
+
+    #[test]
+    fn test_reasons() {
+        let reasons: Vec<String> = SAMPLE_DATA.iter()
+            .map(|value| PositionPolicy::Chars.check(value).unwrap().reason)
+            .collect();
+        assert_eq!(reasons, [
+            "only position 1 is 'a'",
+            "neither position 1 ('c') nor 3 ('e') is 'b'",
+            "positions 2 and 9 are both 'c'",
+        ]);
+        assert_eq!(
+            CountPolicy.check(&SAMPLE_DATA[1]).unwrap(),
+            Verdict { valid: false, reason: "found 0 of 'b', need 1–3".to_string() });
+    }
+
+    #[test]
+    fn test_position_multibyte() {
+        let values = [
+            Password {min: 1, max: 3, character: 'é', password: "éaé"},
+            Password {min: 2, max: 3, character: 'ß', password: "aßc"},
+            Password {min: 1, max: 2, character: '😀', password: "😀ab"},
+        ];
+        let valid: Vec<bool> = values.iter()
+            .map(|value| PositionPolicy::Chars.is_valid(value).unwrap())
+            .collect();
+        assert_eq!(valid, [false, true, true]);
+        assert_eq!(count_valid(&values, &CountPolicy).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_position_graphemes() {
+        // "e" followed by a combining acute accent is one grapheme but two chars
+        let value = Password {min: 2, max: 3, character: 'x', password: "ae\u{301}x"};
+        assert!(PositionPolicy::Graphemes.is_valid(&value).unwrap());
+        assert!(!PositionPolicy::Chars.is_valid(&value).unwrap());
+
+        let value = Password {min: 1, max: 4, character: 'x', password: "ae\u{301}x"};
+        assert!(PositionPolicy::Chars.is_valid(&value).unwrap());
+        assert!(matches!(
+            PositionPolicy::Graphemes.check(&value),
+            Err(RunError::PositionBounds { position: 4, length: 3, .. })));
+    }
+
+    #[test]
+    fn test_position_bounds() {
+        let value = Password {min: 0, max: 2, character: 'a', password: "ab"};
+        assert!(matches!(PositionPolicy::Chars.check(&value), Err(RunError::ZeroPosition(_))));
+
+        let value = Password {min: 1, max: 3, character: 'é', password: "éé"};
+        assert!(matches!(
+            PositionPolicy::Chars.check(&value),
+            Err(RunError::PositionBounds { position: 3, length: 2, .. })));
+    }
+
+    #[test]
+    fn test_report() {
+        let policies = vec![parse_policy("count").unwrap(), parse_policy("position").unwrap()];
+        let records: Vec<(usize, Password)> = SAMPLE_DATA.iter().cloned()
+            .enumerate()
+            .map(|(i, value)| (i + 1, value))
+            .collect();
+        assert_eq!(report(&records, &policies, true).unwrap(), "\
+2: 1-3 b: cdefg
+    count     INVALID  found 0 of 'b', need 1–3
+    position  INVALID  neither position 1 ('c') nor 3 ('e') is 'b'
+3: 2-9 c: ccccccccc
+    position  INVALID  positions 2 and 9 are both 'c'");
+
+        assert_eq!(report(&records[..1], &policies, false).unwrap(), "\
+1: 1-3 a: abcde
+    count     valid    found 1 of 'a', need 1–3
+    position  valid    only position 1 is 'a'");
+
+        let records = parse_records("1-9 a: abc\n0-2 b: bb\n1-3 a: abcde").into_iter()
+            .enumerate()
+            .map(|(i, value)| (i + 1, value.unwrap()))
+            .collect::<Vec<(usize, Password)>>();
+        assert_eq!(report(&records, &policies[1..], true).unwrap(), "\
+1: 1-9 a: abc
+    position  INVALID  Position 9 is past the end of \"abc\" (3 long)
+2: 0-2 b: bb
+    position  INVALID  Positions start at 1, found 0 in \"0-2 b: bb\"");
     }
 }
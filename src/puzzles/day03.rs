@@ -1,82 +1,439 @@
-use crate::RunError;
-use std::{thread, sync::{Arc, Mutex}};
+use crate::{RunError, bench::{self, BenchOptions, Stats}, grid::Grid, solver::{Answer, Solver}};
+use std::{fs, mem, num::NonZeroUsize, ops::RangeInclusive, path::{Path, PathBuf}, thread};
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+const DAY: u8 = 3;
 
-    match part {
-        1 => part1(parsed_data),
-        2 => part2(parsed_data),
-        _ => Err(RunError::BadPartNum)
+pub struct Day03;
+
+impl Solver for Day03 {
+    type Input<'a> = Grid<char>;
+
+    fn name(&self) -> &'static str {
+        "Toboggan Trajectory"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
+    }
+}
+
+fn parse_data(data: &str) -> Result<Grid<char>, RunError> {
+    Grid::parse(DAY, data, "'.' or '#'", |c| matches!(c, '.' | '#').then_some(c))
+}
+
+/// Horizontal and vertical step, (dx, dy)
+pub type Slope = (usize, usize);
+
+/// Tree counts for each slope, in the order they were given, and their product.
+#[derive(Debug, PartialEq)]
+pub struct SlopeReport {
+    pub counts: Vec<(Slope, usize)>,
+    pub product: usize,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    // Smallest step right to try
+    #[arg(long, default_value_t = 0)]
+    min_dx: usize,
+
+    // Largest step right to try
+    #[arg(long, default_value_t = 7)]
+    max_dx: usize,
+
+    // Smallest step down to try
+    #[arg(long, default_value_t = 1)]
+    min_dy: usize,
+
+    // Largest step down to try
+    #[arg(long, default_value_t = 2)]
+    max_dy: usize,
+
+    // Look for the slope hitting the most trees instead of the fewest
+    #[arg(short, long)]
+    most: bool,
+
+    // Number of ranked slopes to list, 0 for all of them
+    #[arg(short, long, default_value_t = 10)]
+    top: usize,
+
+    // Draw the path for one slope, given as "right,down", instead of searching
+    #[arg(short, long, value_parser = parse_slope)]
+    render: Option<Slope>,
+
+    // Highlight the path with ANSI colours
+    #[arg(long, requires = "render")]
+    color: bool,
+
+    // Also write the drawing to a plain PPM image
+    #[arg(long, requires = "render")]
+    image: Option<PathBuf>,
+
+    // Time the char grid against the bit-packed map on a generated map of
+    // "WIDTHxHEIGHT" squares, instead of reading the input
+    #[arg(long, value_parser = parse_size, conflicts_with = "render")]
+    bench_map: Option<(usize, usize)>,
+}
+
+impl Options {
+    /// The map benchmark generates its own input.
+    pub fn needs_input(&self) -> bool {
+        self.bench_map.is_none()
     }
 }
 
-fn parse_data(data: &str) -> Result<Vec<Vec<char>>, RunError> {
-    let lines: Vec<&str> = data[..].split('\n').collect();
+fn parse_size(text: &str) -> Result<(usize, usize), String> {
+    let (width, height) = text.split_once('x')
+        .ok_or_else(|| format!("expected \"WIDTHxHEIGHT\", got {:?}", text))?;
+    let size = |value: &str| value.parse::<usize>()
+        .map_err(|_| format!("expected a square count, got {:?}", value));
 
-    let mut grid: Vec<Vec<char>> = vec![];
+    Ok((size(width)?, size(height)?))
+}
 
-    lines.iter()
-        .for_each(|line| grid.push(line.chars()
-        .collect::<Vec<char>>()));
+fn parse_slope(text: &str) -> Result<Slope, String> {
+    let (dx, dy) = text.split_once(',')
+        .ok_or_else(|| format!("expected \"right,down\", got {:?}", text))?;
+    let step = |value: &str| value.trim().parse::<usize>()
+        .map_err(|_| format!("expected a step count, got {:?}", value));
 
-    Ok(grid)
+    Ok((step(dx)?, step(dy)?))
 }
 
-fn part1(values: Vec<Vec<char>>) -> Result<usize, RunError> {
+fn part1(values: &Grid<char>) -> Result<usize, RunError> {
     // Count trees along slope -1/3
 
-    let (mut x, mut y) = (0, 0);
-    let height = values.len();
-    let width = values[0].len();
-    let mut trees_hit = 0;
+    count_trees(values, (3, 1))
+}
+
+fn part2(values: &Grid<char>) -> Result<usize, RunError> {
+    // Count trees along various slopes, return product
+
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+    Ok(evaluate_slopes(values, &slopes)?.product)
+}
+
+/// Tree positions packed one bit per square, each row padded to whole words.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeMap {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    bits: Vec<u64>,
+}
 
-    while y < height {
-        if values[y][x] == '#' {
-            trees_hit += 1
+impl TreeMap {
+    pub fn from_grid(values: &Grid<char>) -> TreeMap {
+        let width = values.width();
+        let words_per_row = width.div_ceil(64);
+        let mut bits: Vec<u64> = vec![];
+
+        for row in values.rows() {
+            let mut words = vec![0u64; words_per_row];
+            for (x, _) in row.iter().enumerate().filter(|(_, square)| **square == '#') {
+                words[x / 64] |= 1 << (x % 64);
+            }
+            bits.extend(words);
         }
 
-        x = (x + 3) % width;
-        y += 1;
+        TreeMap { width, height: bits.len().checked_div(words_per_row).unwrap_or(0), words_per_row, bits }
     }
 
-    Ok(trees_hit)
+    /// Whether there's a tree at (x, y), with the map repeating to the right.
+    pub fn is_tree(&self, x: usize, y: usize) -> bool {
+        if self.width == 0 || y >= self.height {
+            return false;
+        }
+
+        let x = x % self.width;
+        self.bits[y * self.words_per_row + x / 64] >> (x % 64) & 1 == 1
+    }
+
+    /// Count trees hit going down the slope from the top left.
+    pub fn count_hits(&self, (dx, dy): Slope) -> Result<usize, RunError> {
+        if dy == 0 {
+            return Err(RunError::InputBounds);
+        }
+        if self.width == 0 {
+            return Ok(0);
+        }
+
+        // Step along each row without a division per square
+        let dx = dx % self.width;
+        let mut x = 0;
+        let mut hits = 0;
+
+        for row in self.bits.chunks_exact(self.words_per_row).step_by(dy) {
+            hits += (row[x / 64] >> (x % 64) & 1) as usize;
+
+            x += dx;
+            if x >= self.width {
+                x -= self.width;
+            }
+        }
+
+        Ok(hits)
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.bits.len() * mem::size_of::<u64>()
+    }
 }
 
-fn part2(values: Vec<Vec<char>>) -> Result<usize, RunError> {
-    // Count trees along various slopes, return product
+fn count_trees(values: &Grid<char>, (dx, dy): Slope) -> Result<usize, RunError> {
+    if dy == 0 {
+        return Err(RunError::InputBounds);
+    }
+
+    let width = values.width();
+    if width == 0 {
+        return Ok(0);
+    }
+
+    Ok(values.rows()
+        .step_by(dy)
+        .enumerate()
+        .filter(|(step, row)| row[step * (dx % width) % width] == '#')
+        .count())
+}
+
+/// Count trees along every slope in parallel, with one scoped worker per
+/// available core each taking a contiguous chunk of the slopes.
+pub fn evaluate_slopes(values: &Grid<char>, slopes: &[Slope]) -> Result<SlopeReport, RunError> {
+    let counts = count_slopes(values, slopes, workers())?;
+    let product = counts.iter()
+        .try_fold(1usize, |product, &(_, count)| product.checked_mul(count))
+        .ok_or_else(|| RunError::Overflow(format!("product of {} slope counts", counts.len())))?;
+
+    Ok(SlopeReport { counts, product })
+}
+
+fn workers() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Tree counts in the same order as `slopes`, using at most `workers` threads.
+fn count_slopes(values: &Grid<char>, slopes: &[Slope], workers: usize) -> Result<Vec<(Slope, usize)>, RunError> {
+    let chunk_size = slopes.len().div_ceil(workers.max(1)).max(1);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = slopes.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter()
+                .map(|&slope| Ok((slope, count_trees(values, slope)?)))
+                .collect::<Result<Vec<(Slope, usize)>, RunError>>()))
+            .collect();
+
+        let mut counts: Vec<(Slope, usize)> = Vec::with_capacity(slopes.len());
+        for handle in handles {
+            counts.extend(handle.join().map_err(|_| RunError::PartFailed)??);
+        }
+
+        Ok(counts)
+    })
+}
+
+/// Every slope in the given bounds, ranked from fewest trees to most.
+///
+/// Slopes with equal counts keep their search order, by step down then step right.
+pub fn search_slopes(values: &Grid<char>, dx: RangeInclusive<usize>, dy: RangeInclusive<usize>)
+    -> Result<Vec<(Slope, usize)>, RunError>
+{
+    let slopes: Vec<Slope> = dy
+        .flat_map(|dy| dx.clone().map(move |dx| (dx, dy)))
+        .collect();
+    if slopes.is_empty() {
+        return Err(RunError::InputBounds);
+    }
+
+    // Only the counts matter here; their product would overflow on a big search
+    let mut ranked = count_slopes(values, &slopes, workers())?;
+    ranked.sort_by_key(|&(_, count)| count);
+
+    Ok(ranked)
+}
+
+/// Largest drawing `render_path` will build, in squares.
+const MAX_RENDER_SQUARES: usize = 10_000_000;
+
+/// Repeat the map to the right far enough to hold the whole path, then mark
+/// each square visited with 'O' if open or 'X' if it's a tree.
+pub fn render_path(values: &Grid<char>, (dx, dy): Slope) -> Result<Grid<char>, RunError> {
+    if dy == 0 {
+        return Err(RunError::InputBounds);
+    }
+
+    let width = values.width();
+    let height = values.height();
+    let steps = height.div_ceil(dy);
+
+    // Refuse before allocating rather than run out of memory on a steep slope
+    let tiles = steps.saturating_sub(1).checked_mul(dx)
+        .map(|last_x| last_x.checked_div(width).map_or(1, |tiles| tiles + 1));
+    let drawn_width = tiles.and_then(|tiles| tiles.checked_mul(width));
+    let tiles = match drawn_width.and_then(|drawn| drawn.checked_mul(height)) {
+        Some(squares) if squares <= MAX_RENDER_SQUARES => tiles.unwrap_or(1),
+        _ => return Err(RunError::RenderSize {
+            width: drawn_width.unwrap_or(usize::MAX),
+            height,
+            limit: MAX_RENDER_SQUARES,
+        }),
+    };
+
+    let cells = values.rows()
+        .flat_map(|row| row.iter().cycle().take(width * tiles).copied())
+        .collect();
+    let mut map = Grid::new(width * tiles, values.height(), cells)?;
+
+    for step in 0..steps {
+        if let Some(square) = map.get_mut(step * dx, step * dy) {
+            *square = if *square == '#' { 'X' } else { 'O' };
+        }
+    }
 
-    let height = values.len();
-    let width = values[0].len();
+    Ok(map)
+}
+
+fn paint(square: char) -> String {
+    match square {
+        'O' => format!("\x1b[1;32m{}\x1b[0m", square),
+        'X' => format!("\x1b[1;31m{}\x1b[0m", square),
+        _ => square.to_string(),
+    }
+}
+
+/// Plain (P3) PPM with one pixel per square.
+pub fn to_ppm(map: &Grid<char>) -> String {
+    let mut ppm = format!("P3\n{} {}\n255\n", map.width(), map.height());
+
+    for row in map.rows() {
+        let pixels: Vec<&str> = row.iter()
+            .map(|square| match square {
+                '#' => "34 139 34",
+                'O' => "65 105 225",
+                'X' => "220 20 60",
+                _ => "255 255 255",
+            })
+            .collect();
+
+        ppm += &pixels.join(" ");
+        ppm.push('\n');
+    }
+
+    ppm
+}
+
+fn write_image(path: &Path, map: &Grid<char>) -> Result<(), RunError> {
+    fs::write(path, to_ppm(map))
+        .map_err(|source| RunError::IO { path: path.to_path_buf(), source })
+}
+
+/// A reproducible map of roughly one tree in four squares.
+pub fn synthetic_map(width: usize, height: usize) -> String {
+    // xorshift64, fixed seed
+    let mut state: u64 = 0x2020_0003;
+    let mut rows: Vec<String> = Vec::with_capacity(height);
+
+    for _ in 0..height {
+        let row = (0..width)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                if state.is_multiple_of(4) { '#' } else { '.' }
+            })
+            .collect();
+        rows.push(row);
+    }
+
+    rows.join("\n")
+}
 
-    let grid = Arc::new(values);
-    let trees_hit_product = Arc::new(Mutex::new(1));
+/// Time counting the part 2 slopes with each map representation.
+fn compare_maps(width: usize, height: usize) -> Result<String, RunError> {
+    let options = BenchOptions { warmup: 3, iterations: 20 };
     let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
 
-    let mut handles: Vec<thread::JoinHandle<_>> = vec![];
-    for (dx, dy) in slopes {
-        let trees_hit_product_shared = Arc::clone(&trees_hit_product);
-        let grid_shared = Arc::clone(&grid);
+    let grid = parse_data(&synthetic_map(width, height))?;
+    let tree_map = TreeMap::from_grid(&grid);
 
-        handles.push(thread::spawn(move || {
-            let (mut x, mut y) = (0, 0);
-            let mut trees_hit = 0;
+    let grid_stats = bench::measure(&options, || slopes.iter()
+        .map(|&slope| count_trees(&grid, slope))
+        .sum::<Result<usize, RunError>>())?;
+    let packed_stats = bench::measure(&options, || slopes.iter()
+        .map(|&slope| tree_map.count_hits(slope))
+        .sum::<Result<usize, RunError>>())?;
+    // The same bitset looked up a square at a time, as a random access walk would
+    let lookup_stats = bench::measure(&options, || Ok::<_, RunError>(slopes.iter()
+        .map(|&(dx, dy)| (0..height).step_by(dy).enumerate()
+            .filter(|&(step, y)| tree_map.is_tree(step * dx, y))
+            .count())
+        .sum::<usize>()))?;
 
-            while y < height {
-                if grid_shared[y][x] == '#' {
-                    trees_hit += 1
-                }
+    let mut report = format!("{} slopes on a {}x{} map, {} iterations\n\n",
+        slopes.len(), width, height, options.iterations);
+    report += &format!("{:<8} {:>12} {:>12} {:>12} {:>12}", "map", "bytes", "min", "median", "mean");
 
-                x = (x + dx) % width;
-                y += dy;
-            }
+    let rows: [(&str, usize, Stats); 3] = [
+        ("grid", grid.width() * height * mem::size_of::<char>(), grid_stats),
+        ("bitset", tree_map.bytes(), packed_stats),
+        ("lookup", tree_map.bytes(), lookup_stats),
+    ];
+    for (name, bytes, stats) in rows {
+        report += &format!("\n{:<8} {:>12} {:>12} {:>12} {:>12}",
+            name, bytes, bench::format_ns(stats.min), bench::format_ns(stats.median), bench::format_ns(stats.mean));
+    }
+
+    Ok(report)
+}
+
+/// Find the safest (or most dangerous) slope within the option's bounds,
+/// or draw the path taken down one slope.
+pub fn explore(data: &str, options: &Options) -> Result<String, RunError> {
+    if let Some((width, height)) = options.bench_map {
+        return compare_maps(width, height);
+    }
+
+    let values = parse_data(data)?;
+
+    if let Some(slope) = options.render {
+        let map = render_path(&values, slope)?;
+        if let Some(path) = &options.image {
+            write_image(path, &map)?;
+        }
 
-            *trees_hit_product_shared.lock().unwrap() *= trees_hit;
-        }));
+        let drawing = if options.color { map.map(|&square| paint(square)).to_string() } else { map.to_string() };
+        return Ok(format!("{}\n\n{} trees hit on slope right {}, down {}",
+            drawing, count_trees(&values, slope)?, slope.0, slope.1));
+    }
+    let mut ranked = search_slopes(&values, options.min_dx..=options.max_dx, options.min_dy..=options.max_dy)?;
+    if options.most {
+        ranked.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
     }
 
-    let ret_val = Ok(*trees_hit_product.lock().unwrap());
-    ret_val
+    let ((dx, dy), best) = ranked[0];
+    let mut report = format!("{} trees: {} on slope right {}, down {}\n\n",
+        if options.most { "Most" } else { "Fewest" }, best, dx, dy);
+    report += &format!("{:>4} {:>5} {:>4} {:>6}", "rank", "right", "down", "trees");
+
+    let shown = if options.top == 0 { ranked.len() } else { options.top };
+    for (rank, ((dx, dy), count)) in ranked.iter().take(shown).enumerate() {
+        report += &format!("\n{:>4} {:>5} {:>4} {:>6}", rank + 1, dx, dy, count);
+    }
+
+    Ok(report)
 }
 
 #[cfg(test)]
@@ -94,7 +451,7 @@ mod tests {
 #.##...#...
 #...##....#
 .#..#...#.#";
-    static SAMPLE_DATA: &'static [[char; 11]; 11] = &[
+    static SAMPLE_DATA: &[[char; 11]; 11] = &[
         ['.','.','#','#','.','.','.','.','.','.','.'],
         ['#','.','.','.','#','.','.','.','#','.','.'],
         ['.','#','.','.','.','.','#','.','.','#','.'],
@@ -109,24 +466,192 @@ mod tests {
 
     static SAMPLE_GOALS: [usize; 2] = [7, 336];
 
+    fn sample_grid() -> Grid<char> {
+        Grid::new(11, 11, SAMPLE_DATA.concat()).unwrap()
+    }
+
     #[test]
     fn test_parse() {
         assert_eq!(
             parse_data(SAMPLE_INPUT).unwrap(),
-            SAMPLE_DATA);
+            sample_grid());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_data("..#\n.o.").is_err());
+        assert!(parse_data("..#\n..").is_err());
     }
 
     #[test]
     fn test_part1() {
         assert_eq!(
-            part1(SAMPLE_DATA.map(|line| line.to_vec()).to_vec()).unwrap(),
+            part1(&sample_grid()).unwrap(),
             SAMPLE_GOALS[0]);
     }
 
     #[test]
     fn test_part2() {
         assert_eq!(
-            part2(SAMPLE_DATA.map(|line| line.to_vec()).to_vec()).unwrap(),
+            part2(&sample_grid()).unwrap(),
             SAMPLE_GOALS[1]);
     }
+
+    #[test]
+    fn test_evaluate_slopes() {
+        let grid = sample_grid();
+        let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+
+        for _ in 0..10 {
+            assert_eq!(
+                evaluate_slopes(&grid, &slopes).unwrap(),
+                SlopeReport {
+                    counts: vec![((1, 1), 2), ((3, 1), 7), ((5, 1), 3), ((7, 1), 4), ((1, 2), 2)],
+                    product: 336,
+                });
+        }
+
+        assert_eq!(evaluate_slopes(&grid, &[(2, 3)]).unwrap().counts, [((2, 3), 1)]);
+        assert!(evaluate_slopes(&grid, &[(1, 0)]).is_err());
+    }
+
+    #[test]
+    fn test_count_slopes_chunked() {
+        let grid = sample_grid();
+        let slopes: Vec<Slope> = (1..=3).flat_map(|dy| (0..=12).map(move |dx| (dx, dy))).collect();
+        let expected: Vec<(Slope, usize)> = slopes.iter()
+            .map(|&slope| (slope, count_trees(&grid, slope).unwrap()))
+            .collect();
+
+        for workers in [1, 3, 4, slopes.len(), slopes.len() + 5] {
+            assert_eq!(count_slopes(&grid, &slopes, workers).unwrap(), expected, "{workers} workers");
+        }
+        assert_eq!(count_slopes(&grid, &[], 3).unwrap(), []);
+        assert!(count_slopes(&grid, &[(1, 1), (1, 0), (2, 1)], 2).is_err());
+    }
+
+    #[test]
+    fn test_search_slopes() {
+        let grid = sample_grid();
+        let ranked = search_slopes(&grid, 1..=7, 1..=1).unwrap();
+        assert_eq!(ranked[0], ((2, 1), 1));
+        assert_eq!(ranked.last(), Some(&((3, 1), 7)));
+        assert_eq!(ranked.len(), 7);
+        assert!(ranked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+
+        assert!(search_slopes(&grid, RangeInclusive::new(3, 1), 1..=1).is_err());
+        assert!(search_slopes(&grid, 1..=3, 0..=1).is_err());
+    }
+
+    #[test]
+    fn test_search_slopes_large() {
+        let grid = sample_grid();
+        let ranked = search_slopes(&grid, 0..=5000, 1..=20).unwrap();
+
+        assert_eq!(ranked.len(), 5001 * 20);
+        assert_eq!(ranked[0].1, 0);
+        assert!(ranked.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(count_trees(&grid, (usize::MAX, 1)).unwrap(), count_trees(&grid, (usize::MAX % 11, 1)).unwrap());
+    }
+
+    #[test]
+    fn test_explore() {
+        let options = Options {
+            min_dx: 1,
+            max_dx: 3,
+            min_dy: 1,
+            max_dy: 2,
+            most: true,
+            top: 3,
+            render: None,
+            color: false,
+            image: None,
+            bench_map: None,
+        };
+        assert_eq!(explore(SAMPLE_INPUT, &options).unwrap(), "\
+Most trees: 7 on slope right 3, down 1
+
+rank right down  trees
+   1     3    1      7
+   2     1    1      2
+   3     1    2      2");
+    }
+
+    #[test]
+    fn test_parse_slope() {
+        assert_eq!(parse_slope("3,1"), Ok((3, 1)));
+        assert_eq!(parse_slope(" 1, 2"), Ok((1, 2)));
+        assert!(parse_slope("3").is_err());
+        assert!(parse_slope("3,-1").is_err());
+    }
+
+    #[test]
+    fn test_render_path() {
+        let map = render_path(&sample_grid(), (3, 1)).unwrap();
+        let rows: Vec<String> = map.to_string().lines().map(str::to_string).collect();
+
+        assert_eq!((map.width(), map.height()), (33, 11));
+        assert_eq!(rows[0], "O.##.........##.........##.......");
+        assert_eq!(rows[1], "#..O#...#..#...#...#..#...#...#..");
+        assert_eq!(rows[10], ".#..#...#.#.#..#...#.#.#..#...X.#");
+        assert_eq!(rows.concat().matches('X').count(), SAMPLE_GOALS[0]);
+
+        assert!(matches!(render_path(&sample_grid(), (100_000, 1)),
+            Err(RunError::RenderSize { width: 1_000_010, height: 11, .. })));
+        assert!(matches!(render_path(&sample_grid(), (usize::MAX, 1)), Err(RunError::RenderSize { .. })));
+
+        let map = render_path(&sample_grid(), (1, 2)).unwrap();
+        assert_eq!(map.width(), 11);
+        assert_eq!(map.to_string().matches(['O', 'X']).count(), 6);
+        assert!(render_path(&sample_grid(), (1, 0)).is_err());
+    }
+
+    #[test]
+    fn test_tree_map() {
+        let tree_map = TreeMap::from_grid(&sample_grid());
+        assert!(tree_map.is_tree(2, 0));
+        assert!(!tree_map.is_tree(0, 0));
+        assert!(tree_map.is_tree(13, 0));
+        assert!(!tree_map.is_tree(2, 11));
+        assert_eq!(tree_map.bytes(), 11 * 8);
+
+        for slope in [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2), (0, 1), (12, 3)] {
+            assert_eq!(tree_map.count_hits(slope).unwrap(), count_trees(&sample_grid(), slope).unwrap(), "{slope:?}");
+        }
+        assert!(tree_map.count_hits((1, 0)).is_err());
+    }
+
+    #[test]
+    fn test_tree_map_wide() {
+        let grid = parse_data(&synthetic_map(200, 300)).unwrap();
+        let tree_map = TreeMap::from_grid(&grid);
+
+        for (x, y) in [(0, 0), (63, 5), (64, 5), (127, 9), (128, 9), (199, 299), (450, 17)] {
+            assert_eq!(tree_map.is_tree(x, y), grid[(x % 200, y)] == '#', "({x}, {y})");
+        }
+        for slope in [(3, 1), (64, 1), (199, 2), (523, 7)] {
+            assert_eq!(tree_map.count_hits(slope).unwrap(), count_trees(&grid, slope).unwrap(), "{slope:?}");
+        }
+    }
+
+    #[test]
+    fn test_compare_maps() {
+        let report = compare_maps(100, 50).unwrap();
+        let names: Vec<_> = report.lines().skip(3).map(|line| line.split_whitespace().next().unwrap()).collect();
+        assert_eq!(names, ["grid", "bitset", "lookup"]);
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1000x20"), Ok((1000, 20)));
+        assert!(parse_size("1000").is_err());
+        assert!(parse_size("ax2").is_err());
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let map = render_path(&Grid::new(2, 2, vec!['.', '#', '#', '.']).unwrap(), (1, 1)).unwrap();
+        assert_eq!(to_ppm(&map), "P3\n2 2\n255\n65 105 225 34 139 34\n34 139 34 65 105 225\n");
+        assert_eq!(paint('X'), "\x1b[1;31mX\x1b[0m");
+    }
 }
@@ -1,140 +1,419 @@
-use crate::RunError;
+use std::{collections::BTreeMap, fmt, fs, mem, path::{Path, PathBuf}};
+
+use crate::{RunError, runerror::ParseError, solver::{Answer, Solver}};
 
 use regex::Regex;
+use serde::{Deserialize, Deserializer};
+
+/// Fields a passport may have.
+const PASSPORT_FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+/// One key:value pair exactly as written, and where it starts in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field<'a> {
+    pub key: &'a str,
+    pub value: &'a str,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+}
+
+impl fmt::Display for Field<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{} (line {}, column {})", self.key, self.value, self.line, self.column)
+    }
+}
+
+/// Every pair given for one passport, in input order, with nothing dropped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record<'a> {
+    pub fields: Vec<Field<'a>>,
+}
+
+impl<'a> Record<'a> {
+    /// Raw value of a field, the first one given if it's repeated.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.fields.iter()
+            .find(|field| field.key == key)
+            .map(|field| field.value)
+    }
 
-#[derive(Debug, PartialEq)]
-enum Unit {
-    Cm,
-    Inch,
-    None,
+    /// Keys given more than once, where only the first counts, and keys a
+    /// passport doesn't have.
+    pub fn warnings(&self) -> Vec<Warning<'a>> {
+        self.fields.iter()
+            .enumerate()
+            .filter_map(|(i, field)| match self.fields[..i].iter().find(|earlier| earlier.key == field.key) {
+                Some(first) => Some(Warning::Duplicate { field: field.clone(), first: first.clone() }),
+                None => (!PASSPORT_FIELDS.contains(&field.key)).then(|| Warning::Unknown(field.clone())),
+            })
+            .collect()
+    }
 }
 
-impl TryFrom<&str> for Unit {
-    type Error = RunError;
+/// Something in a record the puzzle doesn't look at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning<'a> {
+    Duplicate { field: Field<'a>, first: Field<'a> },
+    Unknown(Field<'a>),
+}
 
-    fn try_from(input: &str) -> Result<Unit, RunError> {
-        match input {
-        "cm" => Ok(Unit::Cm),
-        "in" => Ok(Unit::Inch),
-        "" => Ok(Unit::None),
-        _ => Err(RunError::Regex(input.to_string()))
+impl fmt::Display for Warning<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::Duplicate { field, first } =>
+                write!(f, "duplicate {} ignored, already given as {}", field, first),
+            Warning::Unknown(field) =>
+                write!(f, "unknown field {}, expected one of {}", field, PASSPORT_FIELDS.join(", ")),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
-struct Height {
-    value: u16,
-    unit: Unit,
+const DAY: u8 = 4;
+
+pub struct Day04;
+
+impl Solver for Day04 {
+    type Input<'a> = Vec<Record<'a>>;
+
+    fn name(&self) -> &'static str {
+        "Passport Processing"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_records(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
+    }
 }
 
-#[derive(Debug, Default, PartialEq)]
-struct Passport <'a> {
-    byr: Option<u16>, // Birth Year
-    iyr: Option<u16>, // Issue Year
-    eyr: Option<u16>, // Expiration Year
-    hgt: Option<Height>, // Height
-    hcl: Option<&'a str>, // Hair Color
-    ecl: Option<&'a str>, // Eye Color
-    pid: Option<&'a str>, // Passport ID
-    cid: Option<u16>, // Country ID
+/// Split the input into records at blank lines, keeping every key:value pair.
+/// Only pairs without a ':' are an error; the keys and values aren't checked.
+fn parse_records(data: &str) -> Result<Vec<Record<'_>>, RunError> {
+    let mut records: Vec<Record> = vec![];
+    let mut record = Record::default();
+
+    for (i, line) in data.split('\n').enumerate() {
+        if line.trim().is_empty() {
+            if !record.fields.is_empty() {
+                records.push(mem::take(&mut record));
+            }
+            continue;
+        }
+
+        for pair in line.split(' ').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once(':')
+                .ok_or_else(|| ParseError::at(DAY, data, pair, "'key:value'"))?;
+            let offset = pair.as_ptr() as usize - line.as_ptr() as usize;
+
+            record.fields.push(Field { key, value, line: i + 1, column: line[..offset].chars().count() + 1 });
+        }
+    }
+
+    if !record.fields.is_empty() {
+        records.push(record);
+    }
+
+    Ok(records)
 }
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+fn part1(values: &[Record]) -> Result<usize, RunError> {
+    // Count valid passports: has all fields, ignoring cid. Only presence
+    // matters here, a value that won't convert is still there
 
-    match part {
-        1 => part1(&parsed_data),
-        2 => part2(&parsed_data),
-        _ => Err(RunError::BadPartNum)
+    Ok(values.iter()
+        .filter(|record| PASSPORT_FIELDS.iter()
+            .filter(|&&key| key != "cid")
+            .all(|key| record.get(key).is_some()))
+        .count())
+}
+
+fn part2(values: &[Record]) -> Result<usize, RunError> {
+    // Count valid passports: has all fields with valid values, ignoring cid
+
+    let schema = Schema::puzzle()?;
+
+    Ok(values.iter().filter(|record| schema.is_valid(record)).count())
+}
+
+/// The puzzle's own rules, in schema form.
+static PUZZLE_SCHEMA: &str = include_str!("day04.toml");
+
+/// Integer bounds, both inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Bounds {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Bounds {
+    fn contains(&self, value: i64) -> bool {
+        (self.min..=self.max).contains(&value)
     }
 }
 
-fn parse_data(data: &str) -> Result<Vec<Passport>, RunError> {
-    let mut passports: Vec<Passport> = vec![];
-    let mut passport: Passport;
+impl fmt::Display for Bounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}–{}", self.min, self.max)
+    }
+}
+
+/// A regex compiled when the schema is loaded, which has to match the whole value.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
 
-    for line in data.split("\n\n").collect::<Vec<&str>>() {
-        passport = Passport::default();
+impl Pattern {
+    fn is_match(&self, value: &str) -> bool {
+        self.regex.is_match(value)
+    }
+}
 
-        let kv_pairs: Vec<&str> = line.split(&['\n', ' ']).collect();
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Pattern, D::Error> {
+        let source = String::deserialize(deserializer)?;
 
-        for pair in kv_pairs {
-            let kv: Vec<&str> = pair.split(':').collect();
-            match kv[0] {
-                "byr" => passport.byr = Some(kv[1].parse::<u16>()?),
-                "iyr" => passport.iyr = Some(kv[1].parse::<u16>()?),
-                "eyr" => passport.eyr = Some(kv[1].parse::<u16>()?),
-                "hgt" => passport.hgt = parse_height(kv[1]).unwrap_or(None),
-                "hcl" => passport.hcl = Some(kv[1]),
-                "ecl" => passport.ecl = Some(kv[1]),
-                "pid" => passport.pid = Some(kv[1]),
-                "cid" => passport.cid = Some(kv[1].parse::<u16>()?),
-                "" => {},
-                _ => {return Err(RunError::ParseString(pair.to_string()))}
-            }
+        // Anchor the whole pattern so alternatives can't match a fragment
+        Regex::new(&format!("^(?:{})$", source))
+            .map(|regex| Pattern { source, regex })
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// A plain decimal integer, without the sign or spaces `str::parse` would allow.
+fn parse_integer(text: &str) -> Option<i64> {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+/// What a field's value has to look like.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Rule {
+    /// An integer between the bounds, written with exactly `digits` digits if given
+    Range {
+        min: i64,
+        max: i64,
+        #[serde(default)]
+        digits: Option<usize>,
+    },
+    Regex { pattern: Pattern },
+    Enum { values: Vec<String> },
+    /// A number immediately followed by one of the units, e.g. "183cm"
+    UnitRange { units: BTreeMap<String, Bounds> },
+}
+
+impl Rule {
+    /// Why the value breaks the rule, as a short kind for tallying and a
+    /// description of the problem, or None if it's fine.
+    pub fn explain(&self, value: &str) -> Option<(&'static str, String)> {
+        match self {
+            Rule::Range { min, max, digits } => {
+                let bounds = Bounds { min: *min, max: *max };
+                let written = value.strip_prefix('-').unwrap_or(value).len();
+                match (parse_integer(value), *digits) {
+                    (None, _) => Some(("not a number", "is not a whole number".to_string())),
+                    (Some(_), Some(digits)) if digits != written =>
+                        Some(("wrong length", format!("is not {} digits", digits))),
+                    (Some(number), _) if bounds.contains(number) => None,
+                    (Some(_), _) => Some(("out of range", format!("outside {}", bounds))),
+                }
+            },
+            Rule::Regex { pattern } => (!pattern.is_match(value))
+                .then(|| ("no match", format!("doesn't match {}", pattern.source))),
+            Rule::Enum { values } => (!values.iter().any(|allowed| allowed == value))
+                .then(|| ("not allowed", format!("not one of {}", values.join(", ")))),
+            Rule::UnitRange { units } => {
+                let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+                let (number, unit) = value.split_at(split);
+                let unit_names = || units.keys().cloned().collect::<Vec<String>>().join(", ");
+
+                match (parse_integer(number), units.get(unit)) {
+                    (None, _) => Some(("not a number", format!("is not a number followed by one of {}", unit_names()))),
+                    (Some(_), None) => Some(("bad unit", format!("unit {:?} not one of {}", unit, unit_names()))),
+                    (Some(number), Some(bounds)) => (!bounds.contains(number))
+                        .then(|| ("out of range", format!("outside {}", bounds))),
+                }
+            },
+        }
+    }
+}
+
+/// One way a passport fails its schema.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure {
+    pub field: String,
+    /// None when the field is missing
+    pub value: Option<String>,
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}: {} {}", self.field, value, self.detail),
+            None => write!(f, "{}: {}", self.field, self.detail),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSpec {
+    pub name: String,
+    #[serde(default)]
+    pub required: bool,
+    /// Any value is accepted without a rule
+    pub rule: Option<Rule>,
+}
+
+/// The fields a passport may have and the rules their values follow.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Schema {
+    pub fields: Vec<FieldSpec>,
+}
+
+impl Schema {
+    pub fn puzzle() -> Result<Schema, RunError> {
+        Schema::from_toml(PUZZLE_SCHEMA)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Schema, RunError> {
+        toml::from_str(text).map_err(|e| RunError::BadSchema(e.to_string()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Schema, RunError> {
+        serde_json::from_str(text).map_err(|e| RunError::BadSchema(e.to_string()))
+    }
+
+    /// Read a schema file, as JSON if it has a `.json` extension and TOML otherwise.
+    pub fn load(path: &Path) -> Result<Schema, RunError> {
+        let text = fs::read_to_string(path)
+            .map_err(|source| RunError::IO { path: path.to_path_buf(), source })?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Schema::from_json(&text),
+            _ => Schema::from_toml(&text),
         }
+    }
 
-       passports.push(passport);
+    /// Every required field is present and every present field follows its rule.
+    /// Fields the schema doesn't mention are ignored.
+    pub fn is_valid(&self, record: &Record) -> bool {
+        self.explain(record).is_empty()
     }
 
-    Ok(passports)
+    /// Each missing field and broken rule, in schema order.
+    pub fn explain(&self, record: &Record) -> Vec<Failure> {
+        self.fields.iter()
+            .filter_map(|field| match record.get(&field.name) {
+                Some(value) => field.rule.as_ref()
+                    .and_then(|rule| rule.explain(value))
+                    .map(|(kind, detail)| Failure {
+                        field: field.name.clone(),
+                        value: Some(value.to_string()),
+                        kind,
+                        detail,
+                    }),
+                None => field.required.then(|| Failure {
+                    field: field.name.clone(),
+                    value: None,
+                    kind: "missing",
+                    detail: "missing".to_string(),
+                }),
+            })
+            .collect()
+    }
 }
 
-fn parse_height(text: &str) -> Result<Option<Height>, RunError> {
-    let re_height = Regex::new(r"(?P<value>\d+)(?P<unit>\w{0,2})").unwrap();
+#[derive(clap::Args, Debug)]
+pub struct Options {
+    // Schema file to validate against instead of the puzzle rules, TOML or JSON
+    #[arg(short, long)]
+    schema: Option<PathBuf>,
 
-    let cap = re_height.captures(text)
-        .ok_or_else(|| RunError::Regex(text.to_string()))?;
+    // List why each passport passes or fails, with a tally of failure reasons
+    #[arg(short, long)]
+    explain: bool,
+}
+
+/// Count the passports that satisfy a schema, optionally explaining each verdict.
+pub fn explore(data: &str, options: &Options) -> Result<String, RunError> {
+    let values = parse_records(data)?;
+    let schema = match &options.schema {
+        Some(path) => Schema::load(path)?,
+        None => Schema::puzzle()?,
+    };
+
+    for (i, record) in values.iter().enumerate() {
+        // A custom schema may check fields the puzzle's passports don't have
+        for warning in record.warnings().iter().filter(|warning| !matches!(warning,
+            Warning::Unknown(field) if schema.fields.iter().any(|spec| spec.name == field.key)))
+        {
+            eprintln!("Warning: passport {}: {}", i + 1, warning);
+        }
+    }
 
-    let value = cap.name("value")
-        .ok_or(RunError::Regex(text.to_string()))?
-        .as_str()
-        .parse()?;
-    let unit = cap.name("unit")
-        .ok_or(RunError::Regex(text.to_string()))?
-        .as_str()
-        .try_into()?;
+    let failures: Vec<Vec<Failure>> = values.iter().map(|record| schema.explain(record)).collect();
+    let valid = failures.iter().filter(|failures| failures.is_empty()).count();
+    let summary = format!("{} of {} passports are valid", valid, values.len());
 
-    Ok(Some(Height { value, unit }))
+    if !options.explain {
+        return Ok(summary);
+    }
+
+    Ok(format!("{}\n\n{}\n\n{}", explain(&failures), summary, histogram(&failures)))
 }
 
-fn part1(values: &[Passport]) -> Result<usize, RunError> {
-    // Count valid passports: has all fields, ignoring cid
+fn explain(failures: &[Vec<Failure>]) -> String {
+    let mut blocks: Vec<String> = vec![];
 
-    Ok(values.iter().filter(|passport|
-        passport.byr.is_some() &&
-        passport.iyr.is_some() &&
-        passport.eyr.is_some() &&
-        passport.hgt.is_some() &&
-        passport.hcl.is_some() &&
-        passport.ecl.is_some() &&
-        passport.pid.is_some())
-        .count())
+    for (i, failures) in failures.iter().enumerate() {
+        let mut block = format!("passport {}: {}", i + 1, if failures.is_empty() { "valid" } else { "invalid" });
+        for failure in failures {
+            block += &format!("\n  {}", failure);
+        }
+        blocks.push(block);
+    }
+
+    blocks.join("\n")
 }
 
-fn part2(values: &[Passport]) -> Result<usize, RunError> {
-    // Count valid passports: has all fields with valid values, ignoring cid
+/// Tally failures by field and kind, most common first.
+fn histogram(failures: &[Vec<Failure>]) -> String {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for failure in failures.iter().flatten() {
+        *counts.entry(format!("{}: {}", failure.field, failure.kind)).or_default() += 1;
+    }
 
-    let re_hcl = Regex::new(r"#[0-9a-f]{6}").unwrap();
-    let re_ecl = Regex::new(r"amb|blu|brn|gry|grn|hzl|oth").unwrap();
-    let re_pid = Regex::new(r"^[0-9]{9}$").unwrap();
-
-    Ok(values.iter().filter(|passport|
-        passport.byr.map_or(false, |x| (1920..2002).contains(&x)) &&
-        passport.iyr.map_or(false, |x| (2010..2020).contains(&x)) &&
-        passport.eyr.map_or(false, |x| (2020..2030).contains(&x)) &&
-        passport.hgt.as_ref().map_or(false, |x|
-            match x.unit {
-                Unit::Cm => (150..193).contains(&x.value),
-                Unit::Inch => (59..76).contains(&x.value
-                ),
-                Unit::None => false,
-        }) &&
-        passport.hcl.map_or(false, |x| re_hcl.is_match(x)) &&
-        passport.ecl.map_or(false, |x| re_ecl.is_match(x)) &&
-        passport.pid.map_or(false, |x| re_pid.is_match(x)))
-        .count())
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+    let mut table = format!("{:>8}  {}", "failures", "reason");
+    for (reason, count) in counts {
+        table += &format!("\n{:>8}  {}", count, reason);
+    }
+
+    table
 }
 
 #[cfg(test)]
@@ -184,53 +463,250 @@ eyr:2022
 
 iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
 
-    static SAMPLE_DATA: &'static [Passport] = &[
-        Passport {byr:Some(1937), iyr:Some(2017), eyr:Some(2020), hgt:Some(Height{ value: 183, unit: Unit::Cm }), hcl:Some("#fffffd"), ecl:Some("gry"), pid:Some("860033327"), cid:Some(147)},
-        Passport {byr:Some(1929), iyr:Some(2013), eyr:Some(2023), hgt:None, hcl:Some("#cfa07d"), ecl:Some("amb"), pid:Some("028048884"), cid:Some(350)},
-        Passport {byr:Some(1931), iyr:Some(2013), eyr:Some(2024), hgt:Some(Height { value: 179, unit: Unit::Cm }), hcl:Some("#ae17e1"), ecl:Some("brn"), pid:Some("760753108"), cid:None},
-        Passport {byr:None, iyr:Some(2011), eyr:Some(2025), hgt:Some(Height{ value: 59, unit: Unit::Inch }), hcl:Some("#cfa07d"), ecl:Some("brn"), pid:Some("166559648"), cid:None},
+    /// Keys of each sample record, in input order
+    static SAMPLE_DATA: &[&str] = &[
+        "ecl pid eyr hcl byr iyr cid hgt",
+        "iyr ecl cid eyr pid hcl byr",
+        "hcl iyr eyr ecl pid byr hgt",
+        "hcl eyr pid iyr ecl hgt",
     ];
 
     static SAMPLE_GOALS: [usize; 3] = [2, 0, 4];
 
+    /// A passport that passes every puzzle rule, to vary one field at a time
+    static VALID_PASSPORT: &str = "byr:1980 iyr:2012 eyr:2030 hgt:74in hcl:#623a2f ecl:grn pid:087499704 cid:100";
+
     #[test]
     fn test_parse() {
-        assert_eq!(
-            parse_data(SAMPLE_INPUT).unwrap(),
-            SAMPLE_DATA);
+        let keys: Vec<String> = parse_records(SAMPLE_INPUT).unwrap().iter()
+            .map(|record| record.fields.iter().map(|field| field.key).collect::<Vec<&str>>().join(" "))
+            .collect();
+
+        assert_eq!(keys, SAMPLE_DATA);
     }
 
     #[test]
-    fn test_parse_height() {
-        assert_eq!(
-            parse_height("100cm").unwrap(),
-            Some(Height{ value: 100, unit: Unit::Cm}));
-        assert_eq!(
-            parse_height("60in").unwrap(),
-            Some(Height{ value: 60, unit: Unit::Inch}));
-        assert_eq!(
-            parse_height("100").unwrap(),
-            Some(Height{ value: 100, unit: Unit::None}));
+    fn test_parse_records() {
+        let records = parse_records("\nbyr:1937 foo:bar\n  hgt:183cm byr:1938\n\n\n\nhgt:tall\n").unwrap();
+        let field = |key, value, line, column| Field { key, value, line, column };
+
+        assert_eq!(records, [
+            Record { fields: vec![
+                field("byr", "1937", 2, 1),
+                field("foo", "bar", 2, 10),
+                field("hgt", "183cm", 3, 3),
+                field("byr", "1938", 3, 13),
+            ]},
+            Record { fields: vec![field("hgt", "tall", 7, 1)] },
+        ]);
+
+        assert!(parse_records("byr:1937 iyr2017").is_err());
+        assert_eq!(parse_records("").unwrap(), []);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let records = parse_records("byr:1937 foo:bar\nhgt:183cm byr:1938 iyr:twenty\n\nhgt:tall").unwrap();
+
+        assert_eq!(records[0].warnings().iter().map(Warning::to_string).collect::<Vec<String>>(), [
+            "unknown field foo:bar (line 1, column 10), expected one of byr, iyr, eyr, hgt, hcl, ecl, pid, cid",
+            "duplicate byr:1938 (line 2, column 11) ignored, already given as byr:1937 (line 1, column 1)",
+        ]);
+        assert_eq!(records[1].warnings(), []);
     }
 
     #[test]
     fn test_part1() {
         assert_eq!(
-            part1(&SAMPLE_DATA).unwrap(),
+            part1(&parse_records(SAMPLE_INPUT).unwrap()).unwrap(),
             SAMPLE_GOALS[0]);
+
+        let unconvertible = "hgt:tall byr:19x0 iyr:2015 eyr:2025 hcl:#123abc ecl:brn pid:000000001\n\n\
+            hgt:70ft byr:1980 iyr:2015 eyr:2025 hcl:#123abc ecl:brn cid:1";
+        assert_eq!(part1(&parse_records(unconvertible).unwrap()).unwrap(), 1);
     }
 
     #[test]
     fn test_part2_invalid() {
         assert_eq!(
-            part2(&parse_data(SAMPLE_INPUT_INVALID).unwrap()).unwrap(),
+            part2(&parse_records(SAMPLE_INPUT_INVALID).unwrap()).unwrap(),
             SAMPLE_GOALS[1]);
     }
 
     #[test]
     fn test_part2_valid() {
         assert_eq!(
-            part2(&parse_data(SAMPLE_INPUT_VALID).unwrap()).unwrap(),
+            part2(&parse_records(SAMPLE_INPUT_VALID).unwrap()).unwrap(),
             SAMPLE_GOALS[2]);
     }
+
+    #[test]
+    fn test_get() {
+        let records = parse_records("byr:1937 hgt:183cm\nbyr:1938").unwrap();
+        assert_eq!(records[0].get("hgt"), Some("183cm"));
+        assert_eq!(records[0].get("byr"), Some("1937"));
+        assert_eq!(records[0].get("cid"), None);
+    }
+
+    #[test]
+    fn test_rules() {
+        let schema = Schema::puzzle().unwrap();
+        let rule = |name: &str| schema.fields.iter()
+            .find(|field| field.name == name)
+            .and_then(|field| field.rule.clone())
+            .unwrap();
+
+        assert!(rule("byr").explain("2001").is_none());
+        assert!(rule("byr").explain("1919").is_some());
+        assert!(rule("byr").explain("19x0").is_some());
+        assert_eq!(rule("byr").explain("01980").map(|(kind, _)| kind), Some("wrong length"));
+        assert!(rule("hgt").explain("150cm").is_none());
+        assert!(rule("hgt").explain("60in").is_none());
+        assert!(rule("hgt").explain("60").is_some());
+        assert!(rule("hgt").explain("60ft").is_some());
+        assert!(rule("hgt").explain("cm").is_some());
+        assert!(rule("hcl").explain("#123abc").is_none());
+        assert!(rule("hcl").explain("#123ab").is_some());
+        assert!(rule("pid").explain("000000001").is_none());
+        assert!(rule("pid").explain("0123456789").is_some());
+
+        let colours = Rule::Enum { values: vec!["red".to_string(), "blue".to_string()] };
+        assert!(colours.explain("red").is_none());
+        assert!(colours.explain("re").is_some());
+    }
+
+    #[test]
+    fn test_rule_boundaries() {
+        let schema = Schema::puzzle().unwrap();
+        let cases = [
+            // byr: four digits, 1920–2002
+            ("byr", "1919", false), ("byr", "1920", true), ("byr", "2002", true), ("byr", "2003", false),
+            ("byr", "+1980", false), ("byr", "19800", false), ("byr", "01980", false),
+            ("byr", "0999", false), ("byr", "992", false),
+            // iyr: four digits, 2010–2020
+            ("iyr", "2009", false), ("iyr", "2010", true), ("iyr", "2020", true), ("iyr", "2021", false),
+            ("iyr", "02015", false),
+            // eyr: four digits, 2020–2030
+            ("eyr", "2019", false), ("eyr", "2020", true), ("eyr", "2030", true), ("eyr", "2031", false),
+            ("eyr", "02025", false),
+            // hgt: 150–193cm or 59–76in
+            ("hgt", "149cm", false), ("hgt", "150cm", true), ("hgt", "193cm", true), ("hgt", "194cm", false),
+            ("hgt", "58in", false), ("hgt", "59in", true), ("hgt", "76in", true), ("hgt", "77in", false),
+            ("hgt", "60in", true), ("hgt", "190cm", true), ("hgt", "190in", false), ("hgt", "190", false),
+            ("hgt", "170mm", false), ("hgt", "cm", false), ("hgt", "170cmx", false), ("hgt", "x170cm", false),
+            // hcl: '#' then exactly six of 0-9 a-f
+            ("hcl", "#123abc", true), ("hcl", "#000000", true), ("hcl", "#123abz", false), ("hcl", "123abc", false),
+            ("hcl", "#12345", false), ("hcl", "#123abcd", false), ("hcl", "#123abcde", false), ("hcl", "x#123abc", false),
+            ("hcl", "#ABCDEF", false),
+            // ecl: exactly one of the listed colours
+            ("ecl", "amb", true), ("ecl", "blu", true), ("ecl", "brn", true), ("ecl", "gry", true),
+            ("ecl", "grn", true), ("ecl", "hzl", true), ("ecl", "oth", true), ("ecl", "wat", false),
+            ("ecl", "xamb", false), ("ecl", "ambx", false), ("ecl", "amb|blu", false),
+            // pid: exactly nine digits, leading zeroes included
+            ("pid", "000000001", true), ("pid", "087499704", true), ("pid", "0123456789", false),
+            ("pid", "12345678", false), ("pid", "12345678a", false), ("pid", "a123456789", false),
+            // cid: anything
+            ("cid", "0", true), ("cid", "99999", true),
+        ];
+
+        for (field, value, valid) in cases {
+            let text = VALID_PASSPORT.split(' ')
+                .map(|pair| if pair.starts_with(field) { format!("{}:{}", field, value) } else { pair.to_string() })
+                .collect::<Vec<String>>()
+                .join(" ");
+            let record = parse_records(&text).unwrap().remove(0);
+
+            assert_eq!(schema.is_valid(&record), valid, "{field}:{value}");
+        }
+    }
+
+    #[test]
+    fn test_required_fields() {
+        let schema = Schema::puzzle().unwrap();
+
+        for field in ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"] {
+            let text = VALID_PASSPORT.split(' ')
+                .filter(|pair| !pair.starts_with(field))
+                .collect::<Vec<&str>>()
+                .join(" ");
+            let record = parse_records(&text).unwrap().remove(0);
+
+            assert_eq!(schema.is_valid(&record), field == "cid", "without {field}");
+        }
+    }
+
+    #[test]
+    fn test_custom_schema() {
+        let toml = r#"
+[[fields]]
+name = "ecl"
+required = true
+rule = { type = "enum", values = ["gry", "brn"] }
+
+[[fields]]
+name = "cid"
+"#;
+        let json = r#"{"fields": [
+            {"name": "ecl", "required": true, "rule": {"type": "enum", "values": ["gry", "brn"]}},
+            {"name": "cid"}
+        ]}"#;
+
+        let records = parse_records(SAMPLE_INPUT).unwrap();
+        for schema in [Schema::from_toml(toml).unwrap(), Schema::from_json(json).unwrap()] {
+            let valid: Vec<bool> = records.iter().map(|record| schema.is_valid(record)).collect();
+            assert_eq!(valid, [true, false, true, true]);
+        }
+    }
+
+    #[test]
+    fn test_bad_schema() {
+        assert!(matches!(Schema::from_toml("[[fields]]\nrequired = true"), Err(RunError::BadSchema(_))));
+        assert!(matches!(Schema::from_json(r#"{"fields": [{"name": "a", "rule": {"type": "regex", "pattern": "("}}]}"#),
+            Err(RunError::BadSchema(_))));
+        assert!(matches!(Schema::from_json(r#"{"fields": [{"name": "a", "rule": {"type": "range", "low": 1}}]}"#),
+            Err(RunError::BadSchema(_))));
+    }
+
+    #[test]
+    fn test_explain() {
+        let schema = Schema::puzzle().unwrap();
+        let records = parse_records("byr:1919 hgt:190in hcl:#123abz pid:12345678 ecl:zzz iyr:2009").unwrap();
+        let failures: Vec<String> = schema.explain(&records[0]).iter().map(Failure::to_string).collect();
+
+        assert_eq!(failures, [
+            "byr: 1919 outside 1920–2002",
+            "iyr: 2009 outside 2010–2020",
+            "eyr: missing",
+            "hgt: 190in outside 59–76",
+            "hcl: #123abz doesn't match #[0-9a-f]{6}",
+            "ecl: zzz not one of amb, blu, brn, gry, grn, hzl, oth",
+            "pid: 12345678 doesn't match [0-9]{9}",
+        ]);
+
+        let hgt = |value| Schema::puzzle().unwrap().fields[3].rule.as_ref().unwrap().explain(value);
+        assert_eq!(hgt("190"), Some(("bad unit", "unit \"\" not one of cm, in".to_string())));
+        assert_eq!(hgt("cm"), Some(("not a number", "is not a number followed by one of cm, in".to_string())));
+        assert_eq!(hgt("160cm"), None);
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut options = Options { schema: None, explain: false };
+        assert_eq!(explore(SAMPLE_INPUT_VALID, &options).unwrap(), "4 of 4 passports are valid");
+
+        options.explain = true;
+        assert_eq!(explore(SAMPLE_INPUT, &options).unwrap(), "\
+passport 1: valid
+passport 2: invalid
+  hgt: missing
+passport 3: valid
+passport 4: invalid
+  byr: missing
+
+2 of 4 passports are valid
+
+failures  reason
+       1  byr: missing
+       1  hgt: missing");
+    }
 }
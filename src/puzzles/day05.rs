@@ -1,37 +1,70 @@
-use crate::RunError;
+use crate::{RunError, runerror::ParseError, solver::{Answer, Solver}};
 
 #[derive(Debug, PartialEq)]
-struct Ticket {
+pub struct Ticket {
     row: usize,
     col: usize,
 }
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+const DAY: u8 = 5;
 
-    match part {
-        1 => part1(&parsed_data),
-        2 => part2(&parsed_data),
-        _ => Err(RunError::BadPartNum)
+pub struct Day05;
+
+impl Solver for Day05 {
+    type Input<'a> = Vec<Ticket>;
+
+    fn name(&self) -> &'static str {
+        "Binary Boarding"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
     }
 }
 
 fn parse_data(data: &str) -> Result<Vec<Ticket>, RunError> {
     let mut tickets: Vec<Ticket> = vec![];
 
-    data.split('\n')
-        .map(|line: &str|
-            line.replace('F', "0")
-                .replace('B', "1")
-                .replace('L', "0")
-                .replace('R', "1")
-        )
-        .for_each(|line: String| {
-            tickets.push(Ticket {
-                    row: usize::from_str_radix(&line[..7], 2).unwrap(),
-                    col: usize::from_str_radix(&line[7..], 2).unwrap(),
-            });
+    for line in data.split('\n') {
+        // 7 row characters (F/B) followed by 3 column characters (L/R)
+        let expected = |i: usize| match i {
+            0..=6 => (['F', 'B'], "'F' or 'B'"),
+            _ => (['L', 'R'], "'L' or 'R'"),
+        };
+        for (i, c) in line.char_indices() {
+            if i >= 10 {
+                return Err(ParseError::at(DAY, data, &line[i..], "end of line").into());
+            }
+            if !expected(i).0.contains(&c) {
+                return Err(ParseError::at(DAY, data, &line[i..], expected(i).1).into());
+            }
+        }
+        if line.len() < 10 {
+            return Err(ParseError::at(DAY, data, &line[line.len()..], expected(line.len()).1).into());
+        }
+
+        let line = line.replace('F', "0")
+            .replace('B', "1")
+            .replace('L', "0")
+            .replace('R', "1");
+
+        tickets.push(Ticket {
+                row: usize::from_str_radix(&line[..7], 2)?,
+                col: usize::from_str_radix(&line[7..], 2)?,
         });
+    }
 
     Ok(tickets)
 }
@@ -79,7 +112,7 @@ mod tests {
     static SAMPLE_PART_1: &str = "BFFFBBFRRR
 FFFBBBFRRR
 BBFFBBFRLL";
-    static SAMPLE_DATA: &'static [Ticket] = &[Ticket{ row: 44, col: 5}];
+    static SAMPLE_DATA: &[Ticket] = &[Ticket{ row: 44, col: 5}];
     static SAMPLE_GOALS: [usize; 2] = [820, 0];
 
     #[test]
@@ -101,6 +134,19 @@ BBFFBBFRLL";
             &[Ticket{ row: 102, col: 4}]);
     }
 
+    #[test]
+    fn test_parse_invalid() {
+        let error = match parse_data("FBFBBFFRLR\nFBFBOFFRLR").unwrap_err() {
+            RunError::Parse(error) => error,
+            e => panic!("unexpected error {e}"),
+        };
+        assert_eq!((error.line, error.column), (2, 5));
+        assert_eq!(error.expected, "'F' or 'B'");
+
+        assert!(parse_data("FBFBBFFRL").is_err());
+        assert!(parse_data("FBFBBFFRLRL").is_err());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(
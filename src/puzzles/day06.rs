@@ -1,18 +1,40 @@
 use std::collections::{HashSet, HashMap};
 
-use crate::RunError;
+use crate::{RunError, runerror::ParseError, solver::{Answer, Solver}};
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+const DAY: u8 = 6;
 
-    match part {
-        1 => part1(&parsed_data),
-        2 => part2(&parsed_data),
-        _ => Err(RunError::BadPartNum)
+pub struct Day06;
+
+impl Solver for Day06 {
+    type Input<'a> = Vec<&'a str>;
+
+    fn name(&self) -> &'static str {
+        "Custom Customs"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
     }
 }
 
 fn parse_data(data: &str) -> Result<Vec<&str>, RunError> {
+    if let Some((i, _)) = data.char_indices().find(|(_, c)| !c.is_ascii_lowercase() && *c != '\n') {
+        return Err(ParseError::at(DAY, data, &data[i..], "a question letter a-z").into());
+    }
+
     Ok(data[..].split("\n\n").collect())
 }
 
@@ -85,13 +107,13 @@ a
 a
 
 b";
-    static SAMPLE_DATA: &'static [[&str; 3]; 1] = &[["abcx", "abcy", "abcz"]];
+    static SAMPLE_DATA: &[[&str; 3]; 1] = &[["abcx", "abcy", "abcz"]];
     static SAMPLE_GOALS: [usize; 2] = [11, 6];
 
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse_data(&SAMPLE_INPUT).unwrap(),
+            parse_data(SAMPLE_INPUT).unwrap(),
             SAMPLE_DATA[0]);
     }
 
@@ -1,6 +1,25 @@
+use crate::solver::Puzzle;
+
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
 pub mod day05;
 pub mod day06;
+
+/// Every implemented puzzle, in calendar order.
+pub static REGISTRY: &[&dyn Puzzle] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+];
+
+/// Look up the registered puzzle for a given day.
+pub fn find(day: u8) -> Option<&'static dyn Puzzle> {
+    REGISTRY.iter()
+        .find(|puzzle| puzzle.day() == day)
+        .copied()
+}
@@ -1,59 +1,77 @@
-use crate::RunError;
+use crate::{RunError, solver::{Answer, Solver}};
 
-pub fn main(part: u8, data: &str) -> Result<usize, RunError> {
-    let parsed_data = parse_data(data)?;
+const DAY: u8 = 0;
 
-    match part {
-        1 => part1(&parsed_data),
-        2 => part2(&parsed_data),
-        _ => Err(RunError::BadPartNum)
+pub struct DayNN;
+
+impl Solver for DayNN {
+    type Input<'a> = Vec<usize>;
+
+    fn name(&self) -> &'static str {
+        "PuzzleName"
+    }
+
+    fn day(&self) -> u8 {
+        DAY
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError> {
+        parse_data(data)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError> {
+        part2(input).map(Answer::from)
     }
 }
 
 fn parse_data(data: &str) -> Result<Vec<usize>, RunError> {
-    let lines: Vec<&str> = data[..].split('\n').collect();
+    // One number per line until the puzzle says otherwise
 
-    todo!();
+    data.split('\n').map(|line| Ok(line.parse()?)).collect()
 }
 
-fn part1(values: &[usize]) -> Result<usize, RunError> {
+fn part1(_values: &[usize]) -> Result<usize, RunError> {
     // What's the goal?
 
-    todo!();
+    Err(RunError::NotImplemented(format!("day{:02} part 1", DAY)))
 }
 
-fn part2(values: &[usize]) -> Result<usize, RunError> {
+fn part2(_values: &[usize]) -> Result<usize, RunError> {
     // What's the goal?
 
-    todo!();
+    Err(RunError::NotImplemented(format!("day{:02} part 2", DAY)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    static SAMPLE_INPUT: &str ="";
-    static SAMPLE_DATA: &'static [i32] = &[];
+    static SAMPLE_INPUT: &str = include_str!("samples/dayNN");
+    static SAMPLE_DATA: &[usize] = &[];
     static SAMPLE_GOALS: [usize; 2] = [0, 0];
 
     #[test]
     fn test_parse() {
         assert_eq!(
-            parse_data(&SAMPLE_INPUT).unwrap(),
+            parse_data(SAMPLE_INPUT).unwrap(),
             SAMPLE_DATA);
     }
 
     #[test]
     fn test_part1() {
         assert_eq!(
-            part1(&SAMPLE_DATA).unwrap(),
+            part1(SAMPLE_DATA).unwrap(),
             SAMPLE_GOALS[0]);
     }
 
     #[test]
     fn test_part2() {
         assert_eq!(
-            part2(&SAMPLE_DATA).unwrap(),
+            part2(SAMPLE_DATA).unwrap(),
             SAMPLE_GOALS[1]);
     }
 }
@@ -1,6 +1,8 @@
 use std::{
+    fmt,
     io,
     num::ParseIntError,
+    path::PathBuf,
 };
 use thiserror::Error;
 
@@ -12,21 +14,149 @@ pub enum RunError {
     #[error("Unable to parse {0}")]
     ParseString(String),
 
+    #[error("{0}")]
+    Parse(#[from] Box<ParseError>),
+
     #[error("{0} is not yet implemented")]
     NotImplemented(String),
 
+    #[error("{0} already exists")]
+    DayExists(String),
+
     #[error("Invalid part number specified")]
     BadPartNum,
 
+    #[error("No day specified")]
+    BadDayNum,
+
+    #[error("Invalid day range: {0}")]
+    BadDayRange(String),
+
     #[error("Puzzle solver failed to run")]
     PartFailed,
 
     #[error("Input value out of bounds")]
     InputBounds,
 
-    #[error("Unable to read file: {0}")]
-    IO(#[from] io::Error),
+    #[error("Position {position} is past the end of {password:?} ({length} long)")]
+    PositionBounds { position: usize, length: usize, password: String },
+
+    #[error("Positions start at 1, found 0 in {0:?}")]
+    ZeroPosition(String),
+
+    #[error("Arithmetic overflow in {0}")]
+    Overflow(String),
+
+    #[error("Unable to access {}: {source}", path.display())]
+    IO { path: PathBuf, source: io::Error },
 
     #[error("Bad regex pattern: {0}")]
-    Regex(String)
+    Regex(String),
+
+    #[error("Unknown password policy: {0}")]
+    BadPolicy(String),
+
+    #[error("Benchmark needs at least one iteration")]
+    BadIterations,
+
+    #[error("Invalid passport schema: {0}")]
+    BadSchema(String),
+
+    #[error("Drawing {width} by {height} squares is over the limit of {limit}")]
+    RenderSize { width: usize, height: usize, limit: usize },
+
+    #[error("Unable to serialize output: {0}")]
+    Serialize(String)
+}
+
+impl RunError {
+    /// Short, stable name for the kind of error, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RunError::ParseInt(_) | RunError::ParseString(_) | RunError::Parse(_) => "parse",
+            RunError::NotImplemented(_) => "not_implemented",
+            RunError::DayExists(_) => "day_exists",
+            RunError::BadPartNum | RunError::BadDayNum | RunError::BadDayRange(_) => "bad_args",
+            RunError::PartFailed => "part_failed",
+            RunError::InputBounds | RunError::PositionBounds { .. } | RunError::ZeroPosition(_)
+                | RunError::RenderSize { .. } => "input_bounds",
+            RunError::Overflow(_) => "overflow",
+            RunError::IO { .. } => "io",
+            RunError::Regex(_) => "regex",
+            RunError::BadPolicy(_) => "bad_args",
+            RunError::BadIterations => "bad_iterations",
+            RunError::BadSchema(_) => "bad_schema",
+            RunError::Serialize(_) => "serialize",
+        }
+    }
+}
+
+/// A parse failure located within a day's input.
+#[derive(Error, Debug, PartialEq)]
+pub struct ParseError {
+    pub day: u8,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Locate `fragment`, which must be a slice of `data`, within the input.
+    pub fn at(day: u8, data: &str, fragment: &str, expected: impl Into<String>) -> ParseError {
+        let offset = (fragment.as_ptr() as usize)
+            .saturating_sub(data.as_ptr() as usize)
+            .min(data.len());
+        let line_start = data[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = data[offset..].find('\n').map_or(data.len(), |i| offset + i);
+
+        ParseError {
+            day,
+            line: data[..offset].matches('\n').count() + 1,
+            column: data[line_start..offset].chars().count() + 1,
+            text: data[line_start..line_end].to_string(),
+            expected: expected.into(),
+        }
+    }
+}
+
+impl From<ParseError> for RunError {
+    fn from(error: ParseError) -> RunError {
+        RunError::Parse(Box::new(error))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let gutter = self.line.to_string().len();
+
+        writeln!(f, "day{:02} line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected)?;
+        writeln!(f, "{} | {}", self.line, self.text)?;
+        write!(f, "{:gutter$} | {:>column$}", "", "^", column = self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_at() {
+        let data = "1-3 a: abcde\n1 3 b: cdefg";
+        let error = ParseError::at(2, data, &data[14..], "'-'");
+
+        assert_eq!(error, ParseError {
+            day: 2,
+            line: 2,
+            column: 2,
+            text: "1 3 b: cdefg".to_string(),
+            expected: "'-'".to_string(),
+        });
+        assert_eq!(
+            error.to_string(),
+            "day02 line 2, column 2: expected '-'\n2 | 1 3 b: cdefg\n  |  ^");
+    }
 }
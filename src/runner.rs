@@ -0,0 +1,221 @@
+use std::{rc::Rc, time::{Duration, Instant}};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{RunError, solver::{Answer, Puzzle}};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+/// Outcome of running one part of one day.
+pub struct RunResult {
+    /// Unknown when the day isn't registered
+    pub year: Option<u16>,
+    pub day: u8,
+    pub part: u8,
+    pub answer: Result<Answer, Rc<RunError>>,
+    pub elapsed: Duration,
+}
+
+impl RunResult {
+    pub fn failed(year: Option<u16>, day: u8, part: u8, error: Rc<RunError>) -> RunResult {
+        RunResult { year, day, part, answer: Err(error), elapsed: Duration::ZERO }
+    }
+
+    fn record(&self) -> Record<'_> {
+        let (answer, error) = match &self.answer {
+            Ok(answer) => (Some(answer), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        Record {
+            year: self.year,
+            day: self.day,
+            part: self.part,
+            answer,
+            duration_ns: self.elapsed.as_nanos() as u64,
+            error_kind: error.map(|e| e.kind()),
+            error: error.map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Machine-readable form of a `RunResult`.
+#[derive(Serialize)]
+struct Record<'a> {
+    year: Option<u16>,
+    day: u8,
+    part: u8,
+    answer: Option<&'a Answer>,
+    duration_ns: u64,
+    error_kind: Option<&'static str>,
+    error: Option<String>,
+}
+
+pub fn run_part(puzzle: &dyn Puzzle, part: u8, data: &str) -> RunResult {
+    let start = Instant::now();
+    let answer = puzzle.run(part, data).map_err(Rc::new);
+    let elapsed = start.elapsed();
+
+    RunResult { year: Some(puzzle.year()), day: puzzle.day(), part, answer, elapsed }
+}
+
+pub fn print_results(results: &[RunResult], format: Format) -> Result<(), RunError> {
+    match format {
+        Format::Text => print_table(results),
+        Format::Json => println!("{}", to_json(results)?),
+        Format::Csv => print!("{}", to_csv(results)),
+    }
+
+    Ok(())
+}
+
+fn to_json(results: &[RunResult]) -> Result<String, RunError> {
+    let records: Vec<Record> = results.iter().map(RunResult::record).collect();
+
+    serde_json::to_string_pretty(&records)
+        .map_err(|e| RunError::Serialize(e.to_string()))
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn to_csv(results: &[RunResult]) -> String {
+    let mut csv = String::from("year,day,part,answer,duration_ns,error_kind,error\n");
+
+    for result in results {
+        let record = result.record();
+        let fields = [
+            record.year.map_or_else(String::new, |year| year.to_string()),
+            record.day.to_string(),
+            record.part.to_string(),
+            record.answer.map_or_else(String::new, |answer| answer.to_string()),
+            record.duration_ns.to_string(),
+            record.error_kind.unwrap_or_default().to_string(),
+            record.error.unwrap_or_default(),
+        ];
+
+        csv += &fields.iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<String>>()
+            .join(",");
+        csv.push('\n');
+    }
+
+    csv
+}
+
+pub fn print_table(results: &[RunResult]) {
+    println!("{:<6} {:<5} {:>16} {:<7} {:>12}",
+        "day", "part", "answer", "status", "time");
+
+    for result in results {
+        let (answer, status) = match &result.answer {
+            Ok(answer) => (answer.summary(), "ok"),
+            Err(_) => ("-".to_string(), "FAILED"),
+        };
+
+        println!("{:<6} {:<5} {:>16} {:<7} {:>12}",
+            format!("day{:02}", result.day),
+            result.part,
+            answer,
+            status,
+            format!("{:.2?}", result.elapsed));
+    }
+
+    for result in results {
+        if let Err(e) = &result.answer {
+            println!("day{:02} part {}: {}", result.day, result.part, e);
+        }
+    }
+}
+
+/// Parse a day list such as "1-6,9" into sorted, de-duplicated day numbers.
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, RunError> {
+    let bad_range = || RunError::BadDayRange(spec.to_string());
+    let mut days: Vec<u8> = vec![];
+
+    for item in spec.split(',').map(str::trim) {
+        let (start, end) = match item.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (item, item),
+        };
+
+        let start: u8 = start.parse().map_err(|_| bad_range())?;
+        let end: u8 = end.parse().map_err(|_| bad_range())?;
+        if start == 0 || end > 25 || start > end {
+            return Err(bad_range());
+        }
+
+        days.extend(start..=end);
+    }
+
+    days.sort();
+    days.dedup();
+
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_results() -> Vec<RunResult> {
+        vec![
+            RunResult {
+                year: Some(2020),
+                day: 1,
+                part: 1,
+                answer: Ok(Answer::UInt(514579)),
+                elapsed: Duration::from_nanos(1500),
+            },
+            RunResult::failed(None, 9, 2, Rc::new(RunError::NotImplemented("day09".to_string()))),
+        ]
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&sample_results()).unwrap()).unwrap();
+        assert_eq!(json, serde_json::json!([
+            {"year": 2020, "day": 1, "part": 1, "answer": 514579, "duration_ns": 1500,
+                "error_kind": null, "error": null},
+            {"year": null, "day": 9, "part": 2, "answer": null, "duration_ns": 0,
+                "error_kind": "not_implemented", "error": "day09 is not yet implemented"},
+        ]));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(to_csv(&sample_results()), "year,day,part,answer,duration_ns,error_kind,error
+2020,1,1,514579,1500,,
+,9,2,,0,not_implemented,day09 is not yet implemented
+");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("3").unwrap(), [3]);
+        assert_eq!(parse_days("1-6,9").unwrap(), [1, 2, 3, 4, 5, 6, 9]);
+        assert_eq!(parse_days("5, 2-3, 3").unwrap(), [2, 3, 5]);
+    }
+
+    #[test]
+    fn test_parse_days_invalid() {
+        assert!(parse_days("").is_err());
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("6-1").is_err());
+        assert!(parse_days("1-26").is_err());
+        assert!(parse_days("a-b").is_err());
+    }
+}
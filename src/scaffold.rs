@@ -0,0 +1,180 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::{RunError, input};
+
+static SKELETON: &str = include_str!("puzzles/skel.rs");
+
+fn puzzles_dir(root: &Path) -> PathBuf {
+    root.join("src").join("puzzles")
+}
+
+/// Fill in the skeleton's placeholders for the given day.
+pub fn render_skeleton(template: &str, day: u8, name: &str) -> String {
+    template
+        .replace("const DAY: u8 = 0;", &format!("const DAY: u8 = {};", day))
+        .replace("DayNN", &format!("Day{:02}", day))
+        .replace("dayNN", &format!("day{:02}", day))
+        .replace("\"PuzzleName\"", &format!("{:?}", name))
+}
+
+/// Add the day's module declaration and registry entry to `puzzles/mod.rs`.
+pub fn register(mod_rs: &str, day: u8) -> Result<String, RunError> {
+    let module = format!("day{:02}", day);
+    let declaration = format!("pub mod {};", module);
+    let entry = format!("    &{}::Day{:02},", module, day);
+
+    if mod_rs.lines().any(|line| line == declaration) {
+        return Err(RunError::DayExists(module));
+    }
+
+    let mut lines: Vec<String> = mod_rs.lines().map(str::to_string).collect();
+
+    // Keep both lists in calendar order
+    let first_module = lines.iter()
+        .position(|line| line.starts_with("pub mod "))
+        .ok_or_else(|| RunError::ParseString("puzzles/mod.rs module list".to_string()))?;
+    let insert_at = lines.iter()
+        .rposition(|line| line.starts_with("pub mod day") && line.as_str() < declaration.as_str())
+        .map_or(first_module, |i| i + 1);
+    lines.insert(insert_at, declaration);
+
+    let registry = lines.iter()
+        .position(|line| line.contains("REGISTRY"))
+        .ok_or_else(|| RunError::ParseString("puzzles/mod.rs registry".to_string()))?;
+    let registry_end = registry + lines[registry..].iter()
+        .position(|line| line.trim() == "];")
+        .ok_or_else(|| RunError::ParseString("puzzles/mod.rs registry".to_string()))?;
+    let insert_at = lines[registry + 1..registry_end].iter()
+        .position(|line| line.as_str() > entry.as_str())
+        .map_or(registry_end, |i| registry + 1 + i);
+    lines.insert(insert_at, entry);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn create_file(path: &Path, contents: &str) -> Result<(), RunError> {
+    fs::write(path, contents)
+        .map_err(|source| RunError::IO { path: path.to_path_buf(), source })
+}
+
+/// Generate `src/puzzles/dayNN.rs` under `root` from the skeleton and wire it up.
+///
+/// Input goes in the data directory, relative to `root` unless `AOC_DATA_DIR`
+/// gives an absolute path.
+pub fn new_day(root: &Path, day: u8, name: &str) -> Result<Vec<PathBuf>, RunError> {
+    if !(1..=25).contains(&day) {
+        return Err(RunError::BadDayRange(day.to_string()));
+    }
+
+    let module = format!("day{:02}", day);
+    let puzzles = puzzles_dir(root);
+    let source_path = puzzles.join(format!("{}.rs", module));
+    if source_path.exists() {
+        return Err(RunError::DayExists(module));
+    }
+
+    let mod_path = puzzles.join("mod.rs");
+    let mod_rs = fs::read_to_string(&mod_path)
+        .map_err(|source| RunError::IO { path: mod_path.clone(), source })?;
+    let mod_rs = register(&mod_rs, day)?;
+
+    let samples = puzzles.join("samples");
+    let sample_path = samples.join(&module);
+    let data_dir = root.join(input::data_dir());
+    let data_path = data_dir.join(&module);
+
+    for dir in [&samples, &data_dir] {
+        fs::create_dir_all(dir)
+            .map_err(|source| RunError::IO { path: dir.to_path_buf(), source })?;
+    }
+
+    create_file(&source_path, &render_skeleton(SKELETON, day, name))?;
+    if let Err(e) = create_file(&mod_path, &mod_rs) {
+        // Don't leave a module behind that nothing declares
+        let _ = fs::remove_file(&source_path);
+        return Err(e);
+    }
+    let mut created = vec![source_path, mod_path];
+
+    // Never clobber existing puzzle input or samples
+    for path in [sample_path, data_path] {
+        if !path.exists() {
+            create_file(&path, "")?;
+            created.push(path);
+        }
+    }
+
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SAMPLE_MOD: &str = "use crate::solver::Puzzle;
+
+pub mod day01;
+pub mod day03;
+
+pub static REGISTRY: &[&dyn Puzzle] = &[
+    &day01::Day01,
+    &day03::Day03,
+];
+";
+
+    #[test]
+    fn test_render_skeleton() {
+        let rendered = render_skeleton(SKELETON, 7, "Handy Haversacks");
+        assert!(rendered.contains("const DAY: u8 = 7;"));
+        assert!(rendered.contains("pub struct Day07;"));
+        assert!(rendered.contains("include_str!(\"samples/day07\")"));
+        assert!(rendered.contains("\"Handy Haversacks\""));
+        assert!(!rendered.contains("NN"));
+        // A new day has to fail cleanly so --all, verify and bench carry on
+        assert!(!rendered.contains("todo!"));
+        assert!(rendered.contains("Err(RunError::NotImplemented(format!(\"day{:02} part 1\", DAY)))"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert_eq!(register(SAMPLE_MOD, 2).unwrap(), "use crate::solver::Puzzle;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+
+pub static REGISTRY: &[&dyn Puzzle] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+];
+");
+
+        let registered = register(SAMPLE_MOD, 12).unwrap();
+        assert!(registered.contains("pub mod day03;\npub mod day12;\n"));
+        assert!(registered.contains("    &day03::Day03,\n    &day12::Day12,\n];"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(puzzles_dir(&root)).unwrap();
+        fs::write(puzzles_dir(&root).join("mod.rs"), SAMPLE_MOD).unwrap();
+
+        let created = new_day(&root, 2, "Password Philosophy");
+        let mod_rs = fs::read_to_string(puzzles_dir(&root).join("mod.rs"));
+        let source_exists = puzzles_dir(&root).join("day02.rs").exists();
+        let data_exists = root.join(input::data_dir()).join("day02").exists();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(created.unwrap().len(), 4);
+        assert!(mod_rs.unwrap().contains("pub mod day02;"));
+        assert!(source_exists);
+        assert!(data_exists);
+    }
+
+    #[test]
+    fn test_register_existing() {
+        assert!(matches!(register(SAMPLE_MOD, 3), Err(RunError::DayExists(_))));
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+use serde::{Serialize, Serializer};
+
+use crate::{RunError, bench::{self, BenchOptions, BenchReport}};
+
+/// A puzzle answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    BigInt(BigInt),
+    Text(String),
+    /// Rows of a grid, printed one per line
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    /// Single-line rendering for tables and records.
+    pub fn summary(&self) -> String {
+        match self {
+            Answer::Grid(rows) => format!("<{} line grid>", rows.len()),
+            answer => answer.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::UInt(value) => write!(f, "{}", value),
+            Answer::BigInt(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+/// Integers that fit are written as numbers, everything else as text.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Int(value) => serializer.serialize_i64(*value),
+            Answer::UInt(value) => serializer.serialize_u64(*value),
+            Answer::Grid(rows) => rows.serialize(serializer),
+            answer => serializer.serialize_str(&answer.to_string()),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Answer {
+        Answer::UInt(value as u64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Answer {
+        Answer::Int(value)
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(value: BigInt) -> Answer {
+        Answer::BigInt(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Answer {
+        Answer::Grid(rows)
+    }
+}
+
+/// A single day's puzzle: how to parse its input and solve both parts.
+pub trait Solver {
+    /// Parsed puzzle input, which may borrow from the raw data.
+    type Input<'a>;
+
+    fn name(&self) -> &'static str;
+
+    fn day(&self) -> u8;
+
+    fn year(&self) -> u16 {
+        2020
+    }
+
+    fn parse<'a>(&self, data: &'a str) -> Result<Self::Input<'a>, RunError>;
+
+    fn part1(&self, input: &Self::Input<'_>) -> Result<Answer, RunError>;
+
+    fn part2(&self, input: &Self::Input<'_>) -> Result<Answer, RunError>;
+}
+
+/// Object-safe view of a `Solver`, used by the registry.
+pub trait Puzzle: Sync {
+    fn name(&self) -> &'static str;
+
+    fn day(&self) -> u8;
+
+    fn year(&self) -> u16;
+
+    fn run(&self, part: u8, data: &str) -> Result<Answer, RunError>;
+
+    /// Time parsing and each part separately.
+    fn bench(&self, data: &str, options: &BenchOptions) -> Result<BenchReport, RunError>;
+}
+
+impl<S: Solver + Sync> Puzzle for S {
+    fn name(&self) -> &'static str {
+        Solver::name(self)
+    }
+
+    fn day(&self) -> u8 {
+        Solver::day(self)
+    }
+
+    fn year(&self) -> u16 {
+        Solver::year(self)
+    }
+
+    fn run(&self, part: u8, data: &str) -> Result<Answer, RunError> {
+        let parsed_data = self.parse(data)?;
+
+        match part {
+            1 => self.part1(&parsed_data),
+            2 => self.part2(&parsed_data),
+            _ => Err(RunError::BadPartNum)
+        }
+    }
+
+    fn bench(&self, data: &str, options: &BenchOptions) -> Result<BenchReport, RunError> {
+        let parse = bench::measure(options, || self.parse(data))?;
+
+        let parsed_data = self.parse(data)?;
+        let part1 = bench::measure(options, || self.part1(&parsed_data))?;
+        let part2 = bench::measure(options, || self.part2(&parsed_data))?;
+
+        Ok(BenchReport {
+            day: Solver::day(self),
+            name: Solver::name(self),
+            parse,
+            part1,
+            part2,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::from(-42i64).to_string(), "-42");
+        assert_eq!(
+            Answer::from(BigInt::from(u64::MAX) * 4).to_string(),
+            "73786976294838206460");
+        assert_eq!(Answer::from("ABC".to_string()).to_string(), "ABC");
+
+        let grid = Answer::from(vec!["#..".to_string(), ".#.".to_string()]);
+        assert_eq!(grid.to_string(), "#..\n.#.");
+        assert_eq!(grid.summary(), "<2 line grid>");
+    }
+
+    #[test]
+    fn test_answer_serialize() {
+        assert_eq!(serde_json::to_string(&Answer::UInt(42)).unwrap(), "42");
+        assert_eq!(serde_json::to_string(&Answer::Int(-42)).unwrap(), "-42");
+        assert_eq!(
+            serde_json::to_string(&Answer::BigInt(BigInt::from(u64::MAX) * 4)).unwrap(),
+            "\"73786976294838206460\"");
+        assert_eq!(
+            serde_json::to_string(&Answer::Grid(vec!["#.".to_string(), ".#".to_string()])).unwrap(),
+            "[\"#.\",\".#\"]");
+    }
+}